@@ -0,0 +1,450 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    Fields, Ident, ItemStruct, LitStr, Token, Type,
+};
+
+use crate::{check_derives, prime};
+
+/// `#[prime_field(modulus = "0x...", repr = uN)]`, optionally with
+/// `reduction = "montgomery"` (the default) or `reduction = "solinas"`.
+pub struct Args {
+    modulus: Vec<u64>,
+    modulus_span: Span,
+    repr: Type,
+    reduction: Reduction,
+}
+
+enum Reduction {
+    /// Elements are kept in Montgomery form.
+    Montgomery,
+    /// Elements are kept canonical and products folded modulo `2^k - c`.
+    Solinas,
+}
+
+impl Parse for Args {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut modulus = None;
+        let mut repr = None;
+        let mut reduction = Reduction::Montgomery;
+
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+
+            match key.to_string().as_str() {
+                "modulus" => {
+                    let lit: LitStr = input.parse()?;
+                    let words = parse_words(&lit.value())
+                        .ok_or_else(|| syn::Error::new_spanned(&lit, "invalid modulus literal"))?;
+
+                    modulus = Some((words, lit.span()));
+                }
+                "repr" => repr = Some(input.parse()?),
+                "reduction" => {
+                    let lit: LitStr = input.parse()?;
+
+                    reduction = match lit.value().as_str() {
+                        "montgomery" => Reduction::Montgomery,
+                        "solinas" => Reduction::Solinas,
+                        _ => {
+                            return Err(syn::Error::new_spanned(
+                                lit,
+                                "expected \"montgomery\" or \"solinas\"",
+                            ))
+                        }
+                    };
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        key,
+                        "expected `modulus`, `repr` or `reduction`",
+                    ))
+                }
+            }
+
+            if input.parse::<Option<Token![,]>>()?.is_none() {
+                break;
+            }
+        }
+
+        let (modulus, modulus_span) =
+            modulus.ok_or_else(|| input.error("missing `modulus = \"...\"`"))?;
+        let repr = repr.ok_or_else(|| input.error("missing `repr = <bituint type>`"))?;
+
+        Ok(Args {
+            modulus,
+            modulus_span,
+            repr,
+            reduction,
+        })
+    }
+}
+
+/// Parses a decimal or `0x` prefixed hex literal into little-endian words
+/// without trailing zero words.
+fn parse_words(src: &str) -> Option<Vec<u64>> {
+    let src = src.replace('_', "");
+    let (digits, radix) = match src.strip_prefix("0x") {
+        Some(hex) => (hex, 16),
+        None => (src.as_str(), 10),
+    };
+
+    if digits.is_empty() {
+        return None;
+    }
+
+    let mut words = vec![];
+
+    for c in digits.chars() {
+        let mut carry = c.to_digit(radix)? as u64;
+
+        for word in words.iter_mut() {
+            let t = *word as u128 * radix as u128 + carry as u128;
+            *word = t as u64;
+            carry = (t >> 64) as u64;
+        }
+
+        if carry != 0 {
+            words.push(carry);
+        }
+    }
+
+    Some(words)
+}
+
+pub fn expand(args: Args, input: ItemStruct) -> syn::Result<TokenStream> {
+    if !matches!(input.fields, Fields::Unit) {
+        return Err(syn::Error::new_spanned(&input, "expected unit struct"));
+    }
+
+    if args.modulus.first().is_none_or(|&word| word & 1 == 0) || !prime::is_prime(&args.modulus) {
+        return Err(syn::Error::new(
+            args.modulus_span,
+            "the modulus must be an odd prime",
+        ));
+    }
+
+    check_derives(&input.attrs, &["Debug", "Default"])?;
+
+    let attrs = &input.attrs;
+    let vis = &input.vis;
+    let name = &input.ident;
+    let repr = &args.repr;
+    let modulus = &args.modulus;
+    let modulus_bits = 64 * modulus.len() as u32 - modulus.last().unwrap().leading_zeros();
+    let ops = ops(name);
+
+    // the internal representation and the context implementing it
+    let (ctx_type, ctx, one, to_inner, from_inner) = match args.reduction {
+        Reduction::Montgomery => (
+            quote!(::librypt_int::Montgomery<#repr>),
+            quote! {{
+                let (n_prime, r, r2) =
+                    ::librypt_int::field::montgomery_params(#name::MODULUS.to_words());
+
+                ::librypt_int::Montgomery::from_raw_parts(
+                    #name::MODULUS,
+                    n_prime,
+                    <#repr>::from_words(r),
+                    <#repr>::from_words(r2),
+                )
+            }},
+            quote!(#name::CTX.one()),
+            quote!(#name::CTX.to_montgomery(value)),
+            quote!(#name::CTX.from_montgomery(self.0)),
+        ),
+        Reduction::Solinas => (
+            quote!(::librypt_int::Solinas<#repr>),
+            quote! {
+                match ::librypt_int::Solinas::with_words(#name::MODULUS, &#name::MODULUS.to_words()) {
+                    Some(ctx) => ctx,
+                    None => panic!("modulus is not of the form 2^k - c with a word sized or sparse c"),
+                }
+            },
+            quote!(<#repr>::ONE),
+            quote!(#name::CTX.reduce(value, <#repr>::ZERO)),
+            quote!(self.0),
+        ),
+    };
+
+    Ok(quote! {
+        #(#attrs)*
+        #vis struct #name(#repr);
+
+        const _: () = assert!(
+            #modulus_bits <= <#repr>::BITS,
+            "modulus does not fit the repr type"
+        );
+
+        impl #name {
+            /// The field modulus.
+            pub const MODULUS: #repr = <#repr>::from_words(::librypt_int::field::words(&[#(#modulus),*]));
+            pub const ZERO: #name = #name(<#repr>::ZERO);
+            pub const ONE: #name = #name(#one);
+
+            const CTX: #ctx_type = #ctx;
+
+            /// Reduces `value` into the field.
+            #[inline]
+            pub fn new(value: #repr) -> #name {
+                #name(#to_inner)
+            }
+
+            /// Returns `None` unless `value` is below the modulus.
+            #[inline]
+            pub fn from_repr(value: #repr) -> Option<#name> {
+                (value < #name::MODULUS).then(|| #name::new(value))
+            }
+
+            /// Returns the canonical representative, below the modulus.
+            #[inline]
+            pub fn to_repr(self) -> #repr {
+                #from_inner
+            }
+
+            #[inline]
+            pub fn is_zero(self) -> bool {
+                self.0 == <#repr>::ZERO
+            }
+
+            #[inline]
+            pub fn square(self) -> #name {
+                #name(#name::CTX.square(self.0))
+            }
+
+            /// Raises `self` to `exp`, in variable time.
+            #[inline]
+            pub fn pow(self, exp: #repr) -> #name {
+                #name(#name::CTX.pow(self.0, exp))
+            }
+
+            /// Multiplicative inverse by Fermat's little theorem, in constant time,
+            /// or `None` for zero.
+            pub fn invert(self) -> Option<#name> {
+                let exp = #name::MODULUS.overflowing_sub(<#repr>::from(2u8)).0;
+
+                (!self.is_zero()).then(|| #name(#name::CTX.pow_ct(self.0, exp)))
+            }
+
+            /// Legendre symbol: `1` for non zero squares, `-1` for non squares and
+            /// `0` for zero.
+            pub fn legendre(self) -> i8 {
+                let exp = #name::MODULUS >> 1;
+                let ret = self.pow(exp);
+
+                if ret.0 == <#repr>::ZERO {
+                    0
+                } else if ret.0 == #name::ONE.0 {
+                    1
+                } else {
+                    -1
+                }
+            }
+
+            #[inline]
+            pub fn is_square(self) -> bool {
+                self.legendre() >= 0
+            }
+
+            /// Square root by Tonelli-Shanks, or `None` if `self` is not a square.
+            /// Runs in variable time.
+            pub fn sqrt(self) -> Option<#name> {
+                match self.legendre() {
+                    0 => return Some(#name::ZERO),
+                    -1 => return None,
+                    _ => {}
+                }
+
+                // modulus - 1 = q * 2^s with q odd
+                let m = #name::MODULUS.overflowing_sub(<#repr>::ONE).0;
+                let s = m.trailing_zeros();
+                let q = m >> s;
+
+                let mut z = #name::ONE + #name::ONE;
+                while z.legendre() != -1 {
+                    z += #name::ONE;
+                }
+
+                let mut m = s;
+                let mut c = z.pow(q);
+                let mut t = self.pow(q);
+                let mut r = self.pow((q >> 1) + <#repr>::ONE);
+
+                while t.0 != #name::ONE.0 {
+                    // least i with t^(2^i) = 1
+                    let mut i = 0;
+                    let mut tt = t;
+                    while tt.0 != #name::ONE.0 {
+                        tt = tt.square();
+                        i += 1;
+                    }
+
+                    let mut b = c;
+                    for _ in 0..m - i - 1 {
+                        b = b.square();
+                    }
+
+                    m = i;
+                    c = b.square();
+                    t *= c;
+                    r *= b;
+                }
+
+                Some(r)
+            }
+
+            #[inline]
+            pub fn to_le_bytes(self) -> [u8; <#repr>::BYTES] {
+                self.to_repr().to_le_bytes()
+            }
+
+            #[inline]
+            pub fn to_be_bytes(self) -> [u8; <#repr>::BYTES] {
+                self.to_repr().to_be_bytes()
+            }
+
+            /// Returns `None` unless the bytes encode a value below the modulus.
+            #[inline]
+            pub fn from_le_bytes(bytes: [u8; <#repr>::BYTES]) -> Option<#name> {
+                #name::from_repr(<#repr>::from_le_bytes(bytes))
+            }
+
+            /// Returns `None` unless the bytes encode a value below the modulus.
+            #[inline]
+            pub fn from_be_bytes(bytes: [u8; <#repr>::BYTES]) -> Option<#name> {
+                #name::from_repr(<#repr>::from_be_bytes(bytes))
+            }
+        }
+
+        #ops
+
+        impl std::ops::Add for #name {
+            type Output = #name;
+
+            #[inline]
+            fn add(self, rhs: #name) -> #name {
+                let (sum, carry) = self.0.ct_add(rhs.0);
+                let (diff, borrow) = sum.ct_sub(#name::MODULUS);
+
+                #name(<#repr>::ct_select(&diff, &sum, borrow & !carry))
+            }
+        }
+
+        impl std::ops::Sub for #name {
+            type Output = #name;
+
+            #[inline]
+            fn sub(self, rhs: #name) -> #name {
+                let (diff, borrow) = self.0.ct_sub(rhs.0);
+                let (sum, _) = diff.ct_add(#name::MODULUS);
+
+                #name(<#repr>::ct_select(&diff, &sum, borrow))
+            }
+        }
+
+        impl std::ops::Mul for #name {
+            type Output = #name;
+
+            #[inline]
+            fn mul(self, rhs: #name) -> #name {
+                #name(#name::CTX.mul(self.0, rhs.0))
+            }
+        }
+
+        impl std::ops::Neg for #name {
+            type Output = #name;
+
+            #[inline]
+            fn neg(self) -> #name {
+                #name::ZERO - self
+            }
+        }
+
+        impl From<#repr> for #name {
+            #[inline]
+            fn from(value: #repr) -> #name {
+                #name::new(value)
+            }
+        }
+
+        impl From<#name> for #repr {
+            #[inline]
+            fn from(value: #name) -> #repr {
+                value.to_repr()
+            }
+        }
+
+        impl Default for #name {
+            #[inline]
+            fn default() -> #name {
+                #name::ZERO
+            }
+        }
+
+        impl std::fmt::Display for #name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                std::fmt::Display::fmt(&self.to_repr(), f)
+            }
+        }
+
+        impl std::fmt::Debug for #name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}({:#x})", stringify!(#name), self.to_repr())
+            }
+        }
+    })
+}
+
+/// Assign and by reference variants of the binary operators.
+fn ops(name: &Ident) -> TokenStream {
+    [
+        (
+            quote!(Add),
+            quote!(add),
+            quote!(AddAssign),
+            quote!(add_assign),
+        ),
+        (
+            quote!(Sub),
+            quote!(sub),
+            quote!(SubAssign),
+            quote!(sub_assign),
+        ),
+        (
+            quote!(Mul),
+            quote!(mul),
+            quote!(MulAssign),
+            quote!(mul_assign),
+        ),
+    ]
+    .into_iter()
+    .map(|(trait_, method, assign_trait, assign_method)| {
+        quote! {
+            impl std::ops::#trait_<&#name> for #name {
+                type Output = #name;
+
+                #[inline]
+                fn #method(self, rhs: &#name) -> #name {
+                    std::ops::#trait_::#method(self, *rhs)
+                }
+            }
+
+            impl std::ops::#assign_trait for #name {
+                #[inline]
+                fn #assign_method(&mut self, rhs: #name) {
+                    *self = std::ops::#trait_::#method(*self, rhs);
+                }
+            }
+
+            impl std::ops::#assign_trait<&#name> for #name {
+                #[inline]
+                fn #assign_method(&mut self, rhs: &#name) {
+                    *self = std::ops::#trait_::#method(*self, *rhs);
+                }
+            }
+        }
+    })
+    .collect()
+}
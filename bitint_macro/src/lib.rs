@@ -1,18 +1,247 @@
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{
-    parse_macro_input, punctuated::Punctuated, token::Paren, Field, Fields, FieldsUnnamed, Index,
-    ItemStruct, LitInt, Member, Path, PathArguments, PathSegment, Token, Type, TypePath, VisPublic,
-    Visibility,
+    parse_macro_input, punctuated::Punctuated, token::Paren, Attribute, Field, Fields,
+    FieldsUnnamed, Index, ItemStruct, LitInt, Member, Meta, NestedMeta, Path, PathArguments,
+    PathSegment, Token, Type, TypePath, VisPublic, Visibility,
 };
 
-use proc_macro2::{Ident, Span};
+use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
+
+mod field;
+mod prime;
+mod signed;
+
+/// Errors on any `#[derive(...)]` of a trait the attribute implements itself.
+fn check_derives(attrs: &[Attribute], generated: &[&str]) -> syn::Result<()> {
+    for attr in attrs {
+        if !attr.path.is_ident("derive") {
+            continue;
+        }
+
+        if let Meta::List(list) = attr.parse_meta()? {
+            for nested in list.nested {
+                if let NestedMeta::Meta(Meta::Path(path)) = nested {
+                    let derived = path.segments.last().unwrap().ident.to_string();
+
+                    if generated.contains(&derived.as_str()) {
+                        return Err(syn::Error::new_spanned(
+                            path,
+                            format!(
+                                "`{}` is implemented by the attribute, remove it from the derive",
+                                derived
+                            ),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Splits `bits` into primitive limb sizes, least significant (and largest) first.
+fn limb_chunks(bits: u32) -> Vec<u32> {
+    let mut chunks = vec![];
+    let mut bits = bits;
+
+    while bits != 0 {
+        let intbits = if bits >= 128 {
+            128
+        } else if bits >= 64 {
+            64
+        } else if bits >= 32 {
+            32
+        } else if bits >= 16 {
+            16
+        } else {
+            8
+        };
+
+        bits -= intbits;
+        chunks.push(intbits);
+    }
+
+    chunks
+}
+
+/// Checked and wrapping method families derived from the `overflowing_*` methods,
+/// shared by the unsigned and signed types. `add_sub_const` makes the add and sub
+/// variants `const fn` when the underlying `overflowing_*` are.
+fn overflow_families(name: &Ident, add_sub_const: TokenStream2) -> TokenStream2 {
+    let families = [
+        (quote!(add), add_sub_const.clone()),
+        (quote!(sub), add_sub_const),
+        (quote!(mul), quote!()),
+    ]
+    .into_iter()
+    .map(|(op, constness)| {
+        let overflowing = Ident::new(&format!("overflowing_{}", op), Span::call_site());
+        let checked = Ident::new(&format!("checked_{}", op), Span::call_site());
+        let wrapping = Ident::new(&format!("wrapping_{}", op), Span::call_site());
+
+        quote! {
+            #[inline]
+            pub #constness fn #checked(self, rhs: #name) -> Option<#name> {
+                match self.#overflowing(rhs) {
+                    (ret, false) => Some(ret),
+                    _ => None,
+                }
+            }
+
+            #[inline]
+            pub #constness fn #wrapping(self, rhs: #name) -> #name {
+                self.#overflowing(rhs).0
+            }
+        }
+    });
+
+    let div_families = [quote!(div), quote!(rem)].into_iter().map(|op| {
+        let overflowing = Ident::new(&format!("overflowing_{}", op), Span::call_site());
+        let checked = Ident::new(&format!("checked_{}", op), Span::call_site());
+        let wrapping = Ident::new(&format!("wrapping_{}", op), Span::call_site());
+
+        quote! {
+            /// Returns `None` if `rhs` is zero or the operation overflows.
+            #[inline]
+            pub fn #checked(self, rhs: #name) -> Option<#name> {
+                if rhs == #name::ZERO {
+                    return None;
+                }
+
+                match self.#overflowing(rhs) {
+                    (ret, false) => Some(ret),
+                    _ => None,
+                }
+            }
+
+            /// # Panics
+            ///
+            /// Panics if `rhs` is zero.
+            #[inline]
+            pub fn #wrapping(self, rhs: #name) -> #name {
+                self.#overflowing(rhs).0
+            }
+        }
+    });
+
+    let shift_families = [quote!(shl), quote!(shr)].into_iter().map(|op| {
+        let overflowing = Ident::new(&format!("overflowing_{}", op), Span::call_site());
+        let checked = Ident::new(&format!("checked_{}", op), Span::call_site());
+        let wrapping = Ident::new(&format!("wrapping_{}", op), Span::call_site());
+
+        quote! {
+            /// Returns `None` if `rhs` is not less than `BITS`.
+            #[inline]
+            pub fn #checked(self, rhs: u32) -> Option<#name> {
+                match self.#overflowing(rhs) {
+                    (ret, false) => Some(ret),
+                    _ => None,
+                }
+            }
+
+            /// Shifts by `rhs % BITS` bits.
+            #[inline]
+            pub fn #wrapping(self, rhs: u32) -> #name {
+                self.#overflowing(rhs).0
+            }
+        }
+    });
+
+    quote! {
+        #(#families)*
+        #(#div_families)*
+        #(#shift_families)*
+
+        #[inline]
+        pub fn checked_neg(self) -> Option<#name> {
+            match self.overflowing_neg() {
+                (ret, false) => Some(ret),
+                _ => None,
+            }
+        }
+
+        #[inline]
+        pub fn wrapping_neg(self) -> #name {
+            self.overflowing_neg().0
+        }
+
+        /// Raises `self` to the power `exp` by squaring, reporting whether any step overflowed.
+        pub fn overflowing_pow(self, exp: u32) -> (#name, bool) {
+            let mut base = self;
+            let mut exp = exp;
+            let mut ret = #name::ONE;
+            let mut overflow = false;
+
+            while exp != 0 {
+                if exp & 1 == 1 {
+                    let (res, o) = ret.overflowing_mul(base);
+                    ret = res;
+                    overflow |= o;
+                }
+
+                exp >>= 1;
+
+                if exp != 0 {
+                    let (res, o) = base.overflowing_mul(base);
+                    base = res;
+                    overflow |= o;
+                }
+            }
+
+            (ret, overflow)
+        }
+
+        #[inline]
+        pub fn pow(self, exp: u32) -> #name {
+            let (ret, overflow) = self.overflowing_pow(exp);
+
+            debug_assert!(!overflow, "attempt to multiply with overflow");
+
+            ret
+        }
+
+        #[inline]
+        pub fn checked_pow(self, exp: u32) -> Option<#name> {
+            match self.overflowing_pow(exp) {
+                (ret, false) => Some(ret),
+                _ => None,
+            }
+        }
+
+        #[inline]
+        pub fn wrapping_pow(self, exp: u32) -> #name {
+            self.overflowing_pow(exp).0
+        }
+    }
+}
+
+#[proc_macro_attribute]
+pub fn bitint(arg: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(arg as signed::Args);
+    let input = parse_macro_input!(input as ItemStruct);
+
+    signed::expand(args, input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+#[proc_macro_attribute]
+pub fn prime_field(arg: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(arg as field::Args);
+    let input = parse_macro_input!(input as ItemStruct);
+
+    field::expand(args, input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
 
 #[proc_macro_attribute]
 pub fn bituint(arg: TokenStream, input: TokenStream) -> TokenStream {
     let bits: u32 = parse_macro_input!(arg as LitInt).base10_parse().unwrap();
     assert!(
-        bits % 8 == 0 && bits != 0,
+        bits.is_multiple_of(8) && bits != 0,
         "Bits need to be 8 aligned and non zero"
     );
     let input = parse_macro_input!(input as ItemStruct);
@@ -22,48 +251,36 @@ pub fn bituint(arg: TokenStream, input: TokenStream) -> TokenStream {
         panic!("Expected unit struct");
     }
 
-    let mut punct = Punctuated::new();
-
-    let mut chunks = vec![];
-    {
-        let mut bits = bits;
-        while bits != 0 {
-            let intbits = if bits >= 128 {
-                128
-            } else if bits >= 64 {
-                64
-            } else if bits >= 32 {
-                32
-            } else if bits >= 16 {
-                16
-            } else {
-                8
-            };
+    if let Err(err) = check_derives(&input.attrs, &["PartialOrd", "Ord", "Debug"]) {
+        return err.to_compile_error().into();
+    }
 
-            bits -= intbits;
-            chunks.push(intbits);
-            let mut segments = Punctuated::new();
-            segments.push(PathSegment {
-                ident: Ident::new(format!("u{}", intbits).as_str(), Span::call_site()),
-                arguments: PathArguments::None,
-            });
+    let mut punct = Punctuated::new();
 
-            punct.push(Field {
-                attrs: vec![],
-                vis: Visibility::Public(VisPublic {
-                    pub_token: Token![pub](Span::call_site()),
-                }),
-                ident: None,
-                colon_token: None,
-                ty: Type::Path(TypePath {
-                    qself: None,
-                    path: Path {
-                        leading_colon: None,
-                        segments,
-                    },
-                }),
-            });
-        }
+    let chunks = limb_chunks(bits);
+
+    for intbits in &chunks {
+        let mut segments = Punctuated::new();
+        segments.push(PathSegment {
+            ident: Ident::new(format!("u{}", intbits).as_str(), Span::call_site()),
+            arguments: PathArguments::None,
+        });
+
+        punct.push(Field {
+            attrs: vec![],
+            vis: Visibility::Public(VisPublic {
+                pub_token: Token![pub](Span::call_site()),
+            }),
+            ident: None,
+            colon_token: None,
+            ty: Type::Path(TypePath {
+                qself: None,
+                path: Path {
+                    leading_colon: None,
+                    segments,
+                },
+            }),
+        });
     }
 
     let input = ItemStruct {
@@ -94,10 +311,25 @@ pub fn bituint(arg: TokenStream, input: TokenStream) -> TokenStream {
         .map(|x| Ident::new(format!("u{}", x).to_string().as_str(), Span::call_site()))
         .collect();
 
-    let first_member_type = types.first().unwrap().clone();
+    let rev_idxs: Vec<_> = idxs.iter().rev().collect();
+    let limb_bits: Vec<_> = chunks.clone();
+    let rev_limb_bits: Vec<_> = chunks.iter().rev().collect();
+    let limb_starts: Vec<_> = chunks
+        .iter()
+        .scan(0u32, |start, chunk| {
+            let ret = *start;
+            *start += chunk;
+            Some(ret)
+        })
+        .collect();
+    let limb_ends: Vec<_> = limb_starts
+        .iter()
+        .zip(&chunks)
+        .map(|(start, chunk)| start + chunk)
+        .collect();
 
     let add_quote = quote! {
-        let mut ret = #name::MIN;
+        let mut ret = #name::ZERO;
         let mut carry = false;
         let mut other_carry = false;
 
@@ -108,16 +340,11 @@ pub fn bituint(arg: TokenStream, input: TokenStream) -> TokenStream {
             (ret.#idxs, other_carry) = ret.#idxs.overflowing_add(rhs.#idxs);
         )*
 
-        if carry || other_carry {
-            ret = #name::MIN;
-            ret.0 = carry as #first_member_type + other_carry as #first_member_type;
-        }
-
         (ret, carry || other_carry)
     };
 
     let sub_quote = quote! {
-        let mut ret = #name::MIN;
+        let mut ret = #name::ZERO;
         let mut carry = false;
         let mut other_carry = false;
 
@@ -128,27 +355,12 @@ pub fn bituint(arg: TokenStream, input: TokenStream) -> TokenStream {
             (ret.#idxs, other_carry) = ret.#idxs.overflowing_sub(rhs.#idxs);
         )*
 
-        if carry || other_carry {
-            ret = #name::MAX;
-            ret.0 -= carry as #first_member_type + other_carry as #first_member_type;
-        }
-
         (ret, carry || other_carry)
     };
 
-    let div_mod_quote = quote! {
-        let mut ret = #name::MIN;
-        let mut rem = self;
-
-        while rem >= rhs {
-            rem -= rhs;
-            ret += #name::from(1);
-        }
-    };
-
     let from_quote = {
         let mut quote = quote! {};
-        let mut bytes = (bits / 8).max(16);
+        let mut bytes = (bits / 8).min(16);
         let mut offset = 0usize;
         let mut idx = 0;
 
@@ -185,7 +397,7 @@ pub fn bituint(arg: TokenStream, input: TokenStream) -> TokenStream {
         }
 
         quote! {
-            let mut ret = #name::MIN;
+            let mut ret = #name::ZERO;
             let bytes = (value as u128).to_le_bytes();
 
             #quote
@@ -233,7 +445,7 @@ pub fn bituint(arg: TokenStream, input: TokenStream) -> TokenStream {
         }
 
         quote! {
-            let mut ret = #name::MIN;
+            let mut ret = #name::ZERO;
 
             #quote
 
@@ -336,14 +548,244 @@ pub fn bituint(arg: TokenStream, input: TokenStream) -> TokenStream {
     };
 
     let byte_count = bits as usize / 8;
+    let overflow_families_quote = overflow_families(&name, quote!(const));
+    let upper_types = &types[1..];
+
+    let bit_ops = [
+        (
+            quote!(BitAnd),
+            quote!(bitand),
+            quote!(BitAndAssign),
+            quote!(bitand_assign),
+            quote!(&),
+        ),
+        (
+            quote!(BitOr),
+            quote!(bitor),
+            quote!(BitOrAssign),
+            quote!(bitor_assign),
+            quote!(|),
+        ),
+        (
+            quote!(BitXor),
+            quote!(bitxor),
+            quote!(BitXorAssign),
+            quote!(bitxor_assign),
+            quote!(^),
+        ),
+    ];
+
+    let bit_ops_quote = bit_ops
+        .iter()
+        .map(|(trait_, method, assign_trait, assign_method, op)| {
+            quote! {
+                impl std::ops::#trait_ for #name {
+                    type Output = #name;
+
+                    #[inline]
+                    fn #method(self, other: #name) -> #name {
+                        #name(#(self.#idxs #op other.#idxs),*)
+                    }
+                }
+
+                impl std::ops::#trait_<&#name> for &#name {
+                    type Output = #name;
+
+                    #[inline]
+                    fn #method(self, other: &#name) -> #name {
+                        *self #op *other
+                    }
+                }
+
+                impl std::ops::#trait_<&#name> for #name {
+                    type Output = #name;
+
+                    #[inline]
+                    fn #method(self, other: &#name) -> #name {
+                        self #op *other
+                    }
+                }
+
+                impl<'a> std::ops::#trait_<#name> for &'a #name {
+                    type Output = #name;
+
+                    #[inline]
+                    fn #method(self, other: #name) -> #name {
+                        *self #op other
+                    }
+                }
+
+                impl std::ops::#assign_trait for #name {
+                    #[inline]
+                    fn #assign_method(&mut self, other: #name) {
+                        *self = *self #op other;
+                    }
+                }
+
+                impl std::ops::#assign_trait<&#name> for #name {
+                    #[inline]
+                    fn #assign_method(&mut self, other: &#name) {
+                        *self = *self #op *other;
+                    }
+                }
+            }
+        });
+
+    let radix_fmt_quote = [
+        (quote!(LowerHex), 4u32, false, "0x"),
+        (quote!(UpperHex), 4, true, "0x"),
+        (quote!(Binary), 1, false, "0b"),
+        (quote!(Octal), 3, false, "0o"),
+    ]
+    .into_iter()
+    .map(|(trait_, digit_bits, upper, prefix)| {
+        quote! {
+            impl std::fmt::#trait_ for #name {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    let digits = ::librypt_int::radix::to_pow2_radix(
+                        &self.to_words(),
+                        #digit_bits,
+                        #upper,
+                    );
+
+                    f.pad_integral(true, #prefix, &digits)
+                }
+            }
+        }
+    });
+
+    let shift_ops = [
+        (
+            quote!(Shl),
+            quote!(shl),
+            quote!(ShlAssign),
+            quote!(shl_assign),
+            quote!(overflowing_shl),
+            quote!(<<),
+            "attempt to shift left with overflow",
+        ),
+        (
+            quote!(Shr),
+            quote!(shr),
+            quote!(ShrAssign),
+            quote!(shr_assign),
+            quote!(overflowing_shr),
+            quote!(>>),
+            "attempt to shift right with overflow",
+        ),
+    ];
+
+    // a single impl generic over the amount, so the output type stays known and
+    // unsuffixed literals still fall back to `i32` as for the primitive types
+    let shift_ops_quote = shift_ops.iter().map(
+        |(trait_, method, assign_trait, assign_method, overflowing, op, msg)| {
+            quote! {
+                impl<T: ::librypt_int::ShiftAmount> std::ops::#trait_<T> for #name {
+                    type Output = #name;
+
+                    /// Shifts by `rhs mod BITS` bits, panicking in debug builds if `rhs`
+                    /// is out of range.
+                    #[inline]
+                    fn #method(self, rhs: T) -> #name {
+                        let (rhs, overflow) = rhs.reduce(#bits);
+
+                        debug_assert!(!overflow, #msg);
+
+                        self.#overflowing(rhs).0
+                    }
+                }
+
+                impl<T: ::librypt_int::ShiftAmount> std::ops::#trait_<T> for &#name {
+                    type Output = #name;
+
+                    #[inline]
+                    fn #method(self, rhs: T) -> #name {
+                        *self #op rhs
+                    }
+                }
+
+                impl<T: ::librypt_int::ShiftAmount> std::ops::#assign_trait<T> for #name {
+                    #[inline]
+                    fn #assign_method(&mut self, rhs: T) {
+                        *self = *self #op rhs;
+                    }
+                }
+            }
+        },
+    );
+    let word_count = bits.div_ceil(64) as usize;
+    let wide_word_count = 2 * word_count;
+    let top_word_mask = if bits.is_multiple_of(64) {
+        u64::MAX
+    } else {
+        (1u64 << (bits % 64)) - 1
+    };
+
+    let (to_words_quote, from_words_quote) = {
+        let mut to_words = quote! {};
+        let mut from_words = vec![];
+        let mut offset = 0usize;
+
+        for (idx, chunk) in chunks.iter().enumerate() {
+            let member = &idxs[idx];
+            let typ = &types[idx];
+            let word = offset / 64;
+            let shift = (offset % 64) as u32;
+
+            if *chunk == 128 {
+                let next = word + 1;
+
+                to_words = quote! {
+                    #to_words
+
+                    words[#word] = self.#member as u64;
+                    words[#next] = (self.#member >> 64) as u64;
+                };
+                from_words.push(quote! {
+                    words[#word] as u128 | (words[#next] as u128) << 64
+                });
+            } else {
+                to_words = quote! {
+                    #to_words
+
+                    words[#word] |= (self.#member as u64) << #shift;
+                };
+                from_words.push(quote! {
+                    (words[#word] >> #shift) as #typ
+                });
+            }
+
+            offset += *chunk as usize;
+        }
+
+        (
+            quote! {
+                let mut words = [0u64; #word_count];
+
+                #to_words
+
+                words
+            },
+            quote! {
+                #name(#(#from_words),*)
+            },
+        )
+    };
 
     quote! {
         #input
 
         impl #name {
-            const MIN: #name = #name(#(#types::MIN),*);
-            const MAX: #name = #name(#(#types::MAX),*);
-            const BITS: u32 = #bits;
+            /// The smallest value, zero.
+            pub const MIN: #name = #name(#(#types::MIN),*);
+            /// The largest value, `2^BITS - 1`.
+            pub const MAX: #name = #name(#(#types::MAX),*);
+            pub const ZERO: #name = #name(#(#types::MIN),*);
+            pub const ONE: #name = #name(1 #(, #upper_types::MIN)*);
+            /// Size of the type in bits.
+            pub const BITS: u32 = #bits;
+            /// Size of the type in bytes, as taken and returned by the byte conversions.
+            pub const BYTES: usize = #byte_count;
 
             pub const fn overflowing_add(self, rhs: #name) -> (#name, bool) {
                 #add_quote
@@ -354,30 +796,469 @@ pub fn bituint(arg: TokenStream, input: TokenStream) -> TokenStream {
             }
 
             pub fn overflowing_mul(self, rhs: #name) -> (#name, bool) {
-                let mut carry_ret = false;
-                let mut ret = #name::MIN;
-                let mut rhs = rhs;
+                let wide = self.mul_words(rhs);
+                let (low, high) = wide.split_at(#word_count);
+                let overflow = low[#word_count - 1] & !#top_word_mask != 0
+                    || high.iter().any(|&word| word != 0);
+
+                (#name::from_words(low.try_into().unwrap()), overflow)
+            }
+
+            /// Constant time [`Self::overflowing_add`].
+            #[inline]
+            pub fn ct_add(self, rhs: #name) -> (#name, ::librypt_int::Choice) {
+                let (ret, carry) = self.overflowing_add(rhs);
+
+                (ret, ::librypt_int::Choice::from(carry as u8))
+            }
+
+            /// Constant time [`Self::overflowing_sub`].
+            #[inline]
+            pub fn ct_sub(self, rhs: #name) -> (#name, ::librypt_int::Choice) {
+                let (ret, borrow) = self.overflowing_sub(rhs);
+
+                (ret, ::librypt_int::Choice::from(borrow as u8))
+            }
+
+            /// Constant time [`Self::wrapping_mul`], always using schoolbook multiplication.
+            pub fn ct_mul(self, rhs: #name) -> #name {
+                let mut wide = [0u64; #wide_word_count];
+
+                ::librypt_int::limbs::schoolbook_mul(&mut wide, &self.to_words(), &rhs.to_words());
+
+                #name::from_words(wide[..#word_count].try_into().unwrap())
+            }
+
+            #[inline]
+            pub fn ct_eq(&self, other: &#name) -> ::librypt_int::Choice {
+                ::librypt_int::ct::eq(&self.to_words(), &other.to_words())
+            }
+
+            #[inline]
+            pub fn ct_lt(&self, other: &#name) -> ::librypt_int::Choice {
+                ::librypt_int::ct::lt(&self.to_words(), &other.to_words())
+            }
+
+            #[inline]
+            pub fn ct_gt(&self, other: &#name) -> ::librypt_int::Choice {
+                other.ct_lt(self)
+            }
+
+            /// Returns `b` if `choice` is set and `a` otherwise, in constant time.
+            #[inline]
+            pub fn ct_select(a: &#name, b: &#name, choice: ::librypt_int::Choice) -> #name {
+                let mut words = a.to_words();
 
-                while rhs != #name::MIN {
-                    let (res, carry) = ret.overflowing_add(self);
-                    ret += res;
-                    carry_ret = carry_ret || carry;
-                    rhs -= #name::from(1);
+                ::librypt_int::ct::assign(&mut words, &b.to_words(), choice);
+
+                #name::from_words(words)
+            }
+
+            /// Swaps `a` and `b` if `choice` is set, in constant time.
+            #[inline]
+            pub fn ct_swap(a: &mut #name, b: &mut #name, choice: ::librypt_int::Choice) {
+                let mut a_words = a.to_words();
+                let mut b_words = b.to_words();
+
+                ::librypt_int::ct::swap(&mut a_words, &mut b_words, choice);
+
+                *a = #name::from_words(a_words);
+                *b = #name::from_words(b_words);
+            }
+
+            /// Returns the full product as `(low, high)` halves, which never overflows.
+            pub fn widening_mul(self, rhs: #name) -> (#name, #name) {
+                #name::split_wide(self.mul_words(rhs))
+            }
+
+            /// Returns `self * rhs + carry` as `(low, high)` halves, which never overflows.
+            pub fn carrying_mul(self, rhs: #name, carry: #name) -> (#name, #name) {
+                let mut wide = self.mul_words(rhs);
+
+                ::librypt_int::limbs::add_assign(&mut wide, &carry.to_words());
+
+                #name::split_wide(wide)
+            }
+
+            /// Full `2 * BITS` bit product as little-endian words.
+            fn mul_words(self, rhs: #name) -> [u64; #wide_word_count] {
+                let mut wide = [0u64; #wide_word_count];
+                let mut scratch = [0u64; ::librypt_int::limbs::mul_scratch_len(#word_count)];
+
+                ::librypt_int::limbs::mul(
+                    &mut wide,
+                    &self.to_words(),
+                    &rhs.to_words(),
+                    &mut scratch,
+                );
+
+                wide
+            }
+
+            /// Splits a `2 * BITS` bit value at bit `BITS`.
+            fn split_wide(wide: [u64; #wide_word_count]) -> (#name, #name) {
+                let low = #name::from_words(wide[..#word_count].try_into().unwrap());
+                let mut high = wide;
+
+                ::librypt_int::limbs::shr_assign(&mut high, #bits);
+
+                (low, #name::from_words(high[..#word_count].try_into().unwrap()))
+            }
+
+            /// Negates in two's complement, overflowing for any value except zero.
+            pub const fn overflowing_neg(self) -> (#name, bool) {
+                let (ret, _) = #name::ZERO.overflowing_sub(self);
+
+                (ret, !ret.is_zero())
+            }
+
+            #[inline]
+            const fn is_zero(self) -> bool {
+                true #(&& self.#idxs == 0)*
+            }
+
+            #overflow_families_quote
+
+            #[inline]
+            pub const fn saturating_add(self, rhs: #name) -> #name {
+                match self.overflowing_add(rhs) {
+                    (ret, false) => ret,
+                    _ => #name::MAX,
+                }
+            }
+
+            #[inline]
+            pub const fn saturating_sub(self, rhs: #name) -> #name {
+                match self.overflowing_sub(rhs) {
+                    (ret, false) => ret,
+                    _ => #name::MIN,
+                }
+            }
+
+            #[inline]
+            pub fn saturating_mul(self, rhs: #name) -> #name {
+                match self.overflowing_mul(rhs) {
+                    (ret, false) => ret,
+                    _ => #name::MAX,
                 }
+            }
 
-                (ret, carry_ret)
+            /// Division cannot overflow for unsigned types, this is plain division.
+            #[inline]
+            pub fn saturating_div(self, rhs: #name) -> #name {
+                self / rhs
+            }
+
+            #[inline]
+            pub fn saturating_pow(self, exp: u32) -> #name {
+                match self.overflowing_pow(exp) {
+                    (ret, false) => ret,
+                    _ => #name::MAX,
+                }
             }
 
             pub fn overflowing_div(self, rhs: #name) -> (#name, bool) {
-                #div_mod_quote
+                if rhs == #name::ZERO {
+                    panic!("attempt to divide by zero");
+                }
 
-                (ret, false)
+                (self.div_rem(rhs).0, false)
             }
 
             pub fn overflowing_rem(self, rhs: #name) -> (#name, bool) {
-                #div_mod_quote
+                if rhs == #name::ZERO {
+                    panic!("attempt to calculate the remainder with a divisor of zero");
+                }
+
+                (self.div_rem(rhs).1, false)
+            }
+
+            pub const fn count_ones(self) -> u32 {
+                0 #(+ self.#idxs.count_ones())*
+            }
+
+            pub const fn count_zeros(self) -> u32 {
+                0 #(+ self.#idxs.count_zeros())*
+            }
+
+            pub const fn leading_zeros(self) -> u32 {
+                let mut zeros = 0;
+
+                #(
+                    if self.#rev_idxs != 0 {
+                        return zeros + self.#rev_idxs.leading_zeros();
+                    }
+
+                    zeros += #rev_limb_bits;
+                )*
+
+                zeros
+            }
+
+            pub const fn trailing_zeros(self) -> u32 {
+                let mut zeros = 0;
+
+                #(
+                    if self.#idxs != 0 {
+                        return zeros + self.#idxs.trailing_zeros();
+                    }
+
+                    zeros += #limb_bits;
+                )*
+
+                zeros
+            }
+
+            /// Number of significant bits, zero for zero.
+            #[inline]
+            pub const fn bits(self) -> u32 {
+                #bits - self.leading_zeros()
+            }
+
+            /// Returns bit `idx`, counting from the least significant bit.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `idx >= BITS`.
+            pub const fn bit(self, idx: u32) -> bool {
+                #(
+                    if idx < #limb_ends {
+                        return (self.#idxs >> (idx - #limb_starts)) & 1 == 1;
+                    }
+                )*
+
+                panic!("bit index out of range")
+            }
+
+            /// Sets bit `idx`, counting from the least significant bit.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `idx >= BITS`.
+            pub const fn set_bit(&mut self, idx: u32, value: bool) {
+                #(
+                    if idx < #limb_ends {
+                        let shift = idx - #limb_starts;
+                        self.#idxs = self.#idxs & !(1 << shift) | (value as #types) << shift;
+                        return;
+                    }
+                )*
+
+                panic!("bit index out of range")
+            }
+
+            /// Parses an optionally `+` prefixed string of digits in the given radix.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `radix` is not in the range `2..=36`.
+            pub fn from_str_radix(
+                src: &str,
+                radix: u32,
+            ) -> Result<#name, ::librypt_int::ParseIntError> {
+                let mut words = [0u64; #word_count];
+
+                ::librypt_int::radix::parse(&mut words, #bits, src, radix)?;
+
+                Ok(#name::from_words(words))
+            }
+
+            /// Shifts left by `rhs % BITS` bits, reporting whether `rhs` was out of range.
+            pub fn overflowing_shl(self, rhs: u32) -> (#name, bool) {
+                let mut words = self.to_words();
+
+                ::librypt_int::limbs::shl_assign(&mut words, rhs % #bits);
+
+                (#name::from_words(words), rhs >= #bits)
+            }
+
+            /// Shifts right by `rhs % BITS` bits, reporting whether `rhs` was out of range.
+            pub fn overflowing_shr(self, rhs: u32) -> (#name, bool) {
+                let mut words = self.to_words();
+
+                ::librypt_int::limbs::shr_assign(&mut words, rhs % #bits);
+
+                (#name::from_words(words), rhs >= #bits)
+            }
+
+            /// Returns the quotient and remainder of `self / rhs`.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `rhs` is zero.
+            pub fn div_rem(self, rhs: #name) -> (#name, #name) {
+                match self.checked_div_rem(rhs) {
+                    Some(ret) => ret,
+                    None => panic!("attempt to divide by zero"),
+                }
+            }
+
+            /// Returns the quotient and remainder of `self / rhs`, or `None` if `rhs` is zero.
+            pub fn checked_div_rem(self, rhs: #name) -> Option<(#name, #name)> {
+                if rhs == #name::ZERO {
+                    return None;
+                }
+
+                let mut quot = [0u64; #word_count];
+                let mut rem = [0u64; #word_count];
+                let mut scratch = [0u64; #wide_word_count + 1];
+
+                ::librypt_int::limbs::div_rem(
+                    &mut quot,
+                    &mut rem,
+                    &self.to_words(),
+                    &rhs.to_words(),
+                    &mut scratch,
+                );
 
-                (rem, false)
+                Some((#name::from_words(quot), #name::from_words(rem)))
+            }
+
+            /// Computes `self^exp mod modulus` with a sliding window, in Montgomery
+            /// form for odd moduli.
+            ///
+            /// Runs in variable time, use [`Self::pow_mod_ct`] for secret exponents.
+            /// Panics if `modulus` is zero.
+            pub fn pow_mod(self, exp: #name, modulus: #name) -> #name {
+                match ::librypt_int::Montgomery::new(modulus) {
+                    Some(ctx) => ctx.from_montgomery(ctx.pow(ctx.to_montgomery(self), exp)),
+                    None => ::librypt_int::pow::sliding_window(
+                        #name::ONE % modulus,
+                        self % modulus,
+                        exp,
+                        |a, b| a.mul_mod(b, modulus),
+                    ),
+                }
+            }
+
+            /// Computes `self^exp mod modulus` with a Montgomery ladder, taking the
+            /// same time for every `self` and `exp` of a given modulus.
+            ///
+            /// Panics if `modulus` is even.
+            pub fn pow_mod_ct(self, exp: #name, modulus: #name) -> #name {
+                let ctx = ::librypt_int::Montgomery::new(modulus)
+                    .expect("constant time pow_mod requires an odd modulus");
+
+                ctx.from_montgomery(ctx.pow_ct(ctx.to_montgomery(self), exp))
+            }
+
+            /// Computes `self + rhs mod modulus`.
+            ///
+            /// Unlike `(self + rhs) % modulus` this is exact even when the sum
+            /// carries out of the type. Operands at or above `modulus` are reduced
+            /// first, at the cost of a division.
+            ///
+            /// Panics if `modulus` is zero.
+            pub fn add_mod(self, rhs: #name, modulus: #name) -> #name {
+                let (lhs, rhs) = (self.reduce_mod(modulus), rhs.reduce_mod(modulus));
+                let (sum, carry) = lhs.overflowing_add(rhs);
+
+                if carry || sum >= modulus {
+                    sum.overflowing_sub(modulus).0
+                } else {
+                    sum
+                }
+            }
+
+            /// Computes `self - rhs mod modulus`, reducing operands at or above
+            /// `modulus` first.
+            ///
+            /// Panics if `modulus` is zero.
+            pub fn sub_mod(self, rhs: #name, modulus: #name) -> #name {
+                let (lhs, rhs) = (self.reduce_mod(modulus), rhs.reduce_mod(modulus));
+                let (diff, borrow) = lhs.overflowing_sub(rhs);
+
+                if borrow {
+                    diff.overflowing_add(modulus).0
+                } else {
+                    diff
+                }
+            }
+
+            /// Computes `-self mod modulus`, reducing `self` first if needed.
+            ///
+            /// Panics if `modulus` is zero.
+            pub fn neg_mod(self, modulus: #name) -> #name {
+                #name::ZERO.sub_mod(self, modulus)
+            }
+
+            /// `self % modulus`, skipping the division for reduced values.
+            #[inline]
+            fn reduce_mod(self, modulus: #name) -> #name {
+                if self < modulus {
+                    self
+                } else {
+                    self % modulus
+                }
+            }
+
+            /// Computes `self * rhs mod modulus` through the double width product, for
+            /// any operands.
+            ///
+            /// Panics if `modulus` is zero.
+            pub fn mul_mod(self, rhs: #name, modulus: #name) -> #name {
+                let mut quot = [0u64; #wide_word_count];
+                let mut rem = [0u64; #word_count];
+                let mut scratch = [0u64; #wide_word_count + #word_count + 1];
+
+                ::librypt_int::limbs::div_rem(
+                    &mut quot,
+                    &mut rem,
+                    &self.mul_words(rhs),
+                    &modulus.to_words(),
+                    &mut scratch,
+                );
+
+                #name::from_words(rem)
+            }
+
+            /// Computes the inverse of `self` modulo `modulus` by the extended
+            /// Euclidean algorithm, or `None` if they are not coprime.
+            ///
+            /// Runs in variable time. Panics if `modulus` is zero.
+            pub fn inv_mod(self, modulus: #name) -> Option<#name> {
+                let (mut r0, mut r1) = (modulus, self % modulus);
+                // t_i * self = r_i mod modulus
+                let (mut t0, mut t1) = (#name::ZERO, #name::ONE % modulus);
+
+                while r1 != #name::ZERO {
+                    let (quot, rem) = r0.div_rem(r1);
+                    let t = t0.sub_mod(quot.mul_mod(t1, modulus), modulus);
+
+                    (r0, r1) = (r1, rem);
+                    (t0, t1) = (t1, t);
+                }
+
+                (r0 == #name::ONE).then_some(t0)
+            }
+
+            /// Miller-Rabin test with the first `rounds` primes as bases, after trial
+            /// division by the primes below 2048.
+            ///
+            /// The bases are fixed, so a composite built to pass them can; use
+            /// [`Self::is_prime_bpsw`] or `librypt_int::prime::is_probable_prime_with_rng`
+            /// for untrusted input. Panics if `rounds` is zero.
+            pub fn is_probable_prime(self, rounds: u32) -> bool {
+                ::librypt_int::prime::is_probable_prime(self, rounds)
+            }
+
+            /// Baillie-PSW test: trial division, a base 2 Miller-Rabin round and a
+            /// strong Lucas test.
+            ///
+            /// Deterministic and exact below `2^64`; no composite passing it is
+            /// known at any size.
+            pub fn is_prime_bpsw(self) -> bool {
+                ::librypt_int::prime::is_prime_bpsw(self)
+            }
+
+            /// Splits the value into little-endian `u64` words.
+            pub const fn to_words(self) -> [u64; #word_count] {
+                #to_words_quote
+            }
+
+            /// Packs little-endian `u64` words, ignoring bits past `BITS`.
+            pub const fn from_words(words: [u64; #word_count]) -> #name {
+                #from_words_quote
             }
 
             pub fn from_le_bytes(bytes: [u8; #byte_count]) -> #name {
@@ -421,6 +1302,34 @@ pub fn bituint(arg: TokenStream, input: TokenStream) -> TokenStream {
             }
         }
 
+        impl ::librypt_int::BitUint for #name {
+            const BITS: u32 = #bits;
+            const ZERO: #name = #name::ZERO;
+            const ONE: #name = #name::ONE;
+
+            type Words = [u64; #word_count];
+            type WideWords = [u64; #wide_word_count];
+
+            #[inline]
+            fn to_words(self) -> [u64; #word_count] {
+                #name::to_words(self)
+            }
+
+            #[inline]
+            fn from_words(words: [u64; #word_count]) -> #name {
+                #name::from_words(words)
+            }
+
+            #[inline]
+            fn widening_mul_words(self, rhs: #name) -> [u64; #wide_word_count] {
+                let mut wide = [0u64; #wide_word_count];
+
+                ::librypt_int::limbs::schoolbook_mul(&mut wide, &self.to_words(), &rhs.to_words());
+
+                wide
+            }
+        }
+
         impl std::ops::Add for #name {
             type Output = #name;
 
@@ -513,6 +1422,28 @@ pub fn bituint(arg: TokenStream, input: TokenStream) -> TokenStream {
             }
         }
 
+        #(#bit_ops_quote)*
+
+        impl std::ops::Not for #name {
+            type Output = #name;
+
+            #[inline]
+            fn not(self) -> #name {
+                #name(#(!self.#idxs),*)
+            }
+        }
+
+        impl std::ops::Not for &#name {
+            type Output = #name;
+
+            #[inline]
+            fn not(self) -> #name {
+                !*self
+            }
+        }
+
+        #(#shift_ops_quote)*
+
         impl std::ops::Add<&#name> for &#name {
             type Output = #name;
 
@@ -684,10 +1615,68 @@ pub fn bituint(arg: TokenStream, input: TokenStream) -> TokenStream {
             }
         }
 
+        impl Ord for #name {
+            fn cmp(&self, other: &#name) -> std::cmp::Ordering {
+                // the last limb is the most significant one
+                #(
+                    match self.#rev_idxs.cmp(&other.#rev_idxs) {
+                        std::cmp::Ordering::Equal => {}
+                        ord => return ord,
+                    }
+                )*
+
+                std::cmp::Ordering::Equal
+            }
+        }
+
+        impl PartialOrd for #name {
+            #[inline]
+            fn partial_cmp(&self, other: &#name) -> Option<std::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl std::str::FromStr for #name {
+            type Err = ::librypt_int::ParseIntError;
+
+            /// Parses an optionally `+` signed decimal string, or hexadecimal, octal and
+            /// binary ones prefixed with `0x`, `0o` or `0b` after the sign.
+            fn from_str(src: &str) -> Result<#name, Self::Err> {
+                let (digits, radix) = ::librypt_int::radix::split_prefix(src)?;
+
+                #name::from_str_radix(digits, radix)
+            }
+        }
+
+        impl std::fmt::Display for #name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.pad_integral(true, "", &::librypt_int::radix::to_decimal(&self.to_words()))
+            }
+        }
+
+        impl std::fmt::Debug for #name {
+            #[inline]
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                // `{:x?}` and `{:X?}` set these bits, which have no stable accessor
+                #[allow(deprecated)]
+                let flags = f.flags();
+
+                if flags & (1 << 4) != 0 {
+                    std::fmt::LowerHex::fmt(self, f)
+                } else if flags & (1 << 5) != 0 {
+                    std::fmt::UpperHex::fmt(self, f)
+                } else {
+                    std::fmt::Display::fmt(self, f)
+                }
+            }
+        }
+
+        #(#radix_fmt_quote)*
+
         impl Default for #name {
             #[inline]
             fn default() -> #name {
-                #name::MIN
+                #name::ZERO
             }
         }
 
@@ -0,0 +1,441 @@
+//! Primality check for `#[prime_field]` moduli at expansion time, the Baillie-PSW
+//! test of `librypt_int::prime` over plain word vectors.
+
+use std::cmp::Ordering;
+
+/// Bound of the trial division sieve.
+const SIEVE_LIMIT: u64 = 2048;
+
+/// Baillie-PSW on the little-endian words of `n`: trial division, a base 2
+/// strong probable prime test and a strong Lucas probable prime test.
+pub fn is_prime(n: &[u64]) -> bool {
+    let len = n
+        .iter()
+        .rposition(|&word| word != 0)
+        .map_or(0, |top| top + 1);
+    let n = &n[..len];
+
+    if n.len() <= 1 {
+        let small = n.first().copied().unwrap_or(0);
+
+        if small < SIEVE_LIMIT * SIEVE_LIMIT {
+            return small >= 2 && (2..).take_while(|p| p * p <= small).all(|p| small % p != 0);
+        }
+    }
+
+    if small_primes().any(|p| rem_word(n, p) == 0) {
+        return false;
+    }
+
+    let ctx = Montgomery::new(n);
+
+    ctx.miller_rabin_2() && ctx.strong_lucas()
+}
+
+fn small_primes() -> impl Iterator<Item = u64> {
+    (2..SIEVE_LIMIT).filter(|&i| (2..).take_while(|p| p * p <= i).all(|p| i % p != 0))
+}
+
+/// Montgomery arithmetic modulo an odd `n`, with `R = 2^(64 * n.len())`.
+struct Montgomery<'a> {
+    n: &'a [u64],
+    /// `-n^-1 mod 2^64`
+    n_prime: u64,
+    /// `R mod n`, one in Montgomery form
+    r: Vec<u64>,
+    /// `R^2 mod n`
+    r2: Vec<u64>,
+}
+
+impl<'a> Montgomery<'a> {
+    fn new(n: &'a [u64]) -> Montgomery<'a> {
+        let mut inv = 1u64;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(n[0].wrapping_mul(inv)));
+        }
+
+        let mut r = vec![0; n.len()];
+        r[0] = 1;
+        for _ in 0..64 * n.len() {
+            r = add_mod(&r, &r, n);
+        }
+
+        let mut r2 = r.clone();
+        for _ in 0..64 * n.len() {
+            r2 = add_mod(&r2, &r2, n);
+        }
+
+        Montgomery {
+            n,
+            n_prime: inv.wrapping_neg(),
+            r,
+            r2,
+        }
+    }
+
+    /// Montgomery product `a * b / R mod n` (CIOS) for `a, b < n`.
+    fn mul(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let n = self.n;
+        let mut out = vec![0u64; n.len() + 2];
+
+        for &word in b {
+            let mut carry = 0u64;
+
+            for j in 0..n.len() {
+                let t = out[j] as u128 + a[j] as u128 * word as u128 + carry as u128;
+                out[j] = t as u64;
+                carry = (t >> 64) as u64;
+            }
+
+            let t = out[n.len()] as u128 + carry as u128;
+            out[n.len()] = t as u64;
+            out[n.len() + 1] = (t >> 64) as u64;
+
+            let m = out[0].wrapping_mul(self.n_prime);
+            let t = out[0] as u128 + m as u128 * n[0] as u128;
+            let mut carry = (t >> 64) as u64;
+
+            for j in 1..n.len() {
+                let t = out[j] as u128 + m as u128 * n[j] as u128 + carry as u128;
+                out[j - 1] = t as u64;
+                carry = (t >> 64) as u64;
+            }
+
+            let t = out[n.len()] as u128 + carry as u128;
+            out[n.len() - 1] = t as u64;
+            out[n.len()] = out[n.len() + 1] + (t >> 64) as u64;
+            out[n.len() + 1] = 0;
+        }
+
+        // below 2 * n, one subtraction at most
+        if out[n.len()] != 0 || cmp(&out[..n.len()], n).is_ge() {
+            sub_assign(&mut out, n);
+        }
+
+        out.truncate(n.len());
+        out
+    }
+
+    fn to_montgomery(&self, x: &[u64]) -> Vec<u64> {
+        self.mul(x, &self.r2)
+    }
+
+    fn pow(&self, base: &[u64], exp: &[u64]) -> Vec<u64> {
+        let mut ret = self.r.clone();
+
+        for i in (0..bit_len(exp)).rev() {
+            ret = self.mul(&ret, &ret);
+
+            if bit(exp, i) {
+                ret = self.mul(&ret, base);
+            }
+        }
+
+        ret
+    }
+
+    /// Strong probable prime test to base 2.
+    fn miller_rabin_2(&self) -> bool {
+        let n = self.n;
+
+        // n - 1 = d * 2^s with d odd
+        let mut n_minus_one = n.to_vec();
+        sub_assign(&mut n_minus_one, &[1]);
+        let s = trailing_zeros(&n_minus_one);
+        let d = shr(&n_minus_one, s);
+
+        let one = &self.r;
+        let minus_one = self.to_montgomery(&n_minus_one);
+        let mut x = self.pow(&add_mod(one, one, n), &d);
+
+        if x == *one || x == minus_one {
+            return true;
+        }
+
+        for _ in 1..s {
+            x = self.mul(&x, &x);
+
+            if x == minus_one {
+                return true;
+            }
+
+            if x == *one {
+                return false;
+            }
+        }
+
+        false
+    }
+
+    /// Strong Lucas probable prime test with Selfridge's parameters: the first
+    /// `D` of 5, -7, 9, -11, ... with `(D / n) = -1`, `P = 1`, `Q = (1 - D) / 4`.
+    fn strong_lucas(&self) -> bool {
+        let n = self.n;
+        let mut d = 5i64;
+
+        loop {
+            match jacobi(d, n) {
+                -1 => break,
+                0 => return false,
+                _ => {}
+            }
+
+            // a square never finds a D
+            if d == 13 && is_square(n) {
+                return false;
+            }
+
+            d = if d > 0 { -d - 2 } else { -d + 2 };
+        }
+
+        let signed = |value: i64| {
+            let magnitude = self.to_montgomery(&word_vec(value.unsigned_abs(), n.len()));
+
+            if value < 0 {
+                sub_mod(&vec![0; n.len()], &magnitude, n)
+            } else {
+                magnitude
+            }
+        };
+
+        let big_d = signed(d);
+        let q = signed((1 - d) / 4);
+
+        // n + 1 = k * 2^s with k odd
+        let mut k = n.to_vec();
+        k.push(0);
+        add_assign(&mut k, &[1]);
+        let s = trailing_zeros(&k);
+        let k = shr(&k, s);
+
+        let (mut u, mut v, mut qk) = (self.r.clone(), self.r.clone(), q.clone());
+
+        for i in (0..bit_len(&k) - 1).rev() {
+            // U_2j = U_j V_j, V_2j = V_j^2 - 2 Q^j
+            u = self.mul(&u, &v);
+            v = sub_mod(&self.mul(&v, &v), &add_mod(&qk, &qk, n), n);
+            qk = self.mul(&qk, &qk);
+
+            if bit(&k, i) {
+                // U_j+1 = (P U_j + V_j) / 2, V_j+1 = (D U_j + P V_j) / 2
+                let u_next = add_mod(&u, &v, n);
+                let v_next = add_mod(&self.mul(&big_d, &u), &v, n);
+                u = half_mod(&u_next, n);
+                v = half_mod(&v_next, n);
+                qk = self.mul(&qk, &q);
+            }
+        }
+
+        let zero = vec![0; n.len()];
+        if u == zero || v == zero {
+            return true;
+        }
+
+        for _ in 1..s {
+            v = sub_mod(&self.mul(&v, &v), &add_mod(&qk, &qk, n), n);
+            qk = self.mul(&qk, &qk);
+
+            if v == zero {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+/// Jacobi symbol `(a / n)` for odd `n`.
+fn jacobi(a: i64, n: &[u64]) -> i32 {
+    let magnitude = a.unsigned_abs();
+    let mut sign = if a < 0 && n[0] & 3 == 3 { -1 } else { 1 };
+
+    let twos = magnitude.trailing_zeros();
+    let odd = magnitude >> twos;
+    if twos % 2 == 1 && matches!(n[0] & 7, 3 | 5) {
+        sign = -sign;
+    }
+
+    if odd & 3 == 3 && n[0] & 3 == 3 {
+        sign = -sign;
+    }
+
+    let (mut a, mut n) = (rem_word(n, odd), odd);
+    let mut ret = sign;
+
+    while a != 0 {
+        while a.is_multiple_of(2) {
+            a /= 2;
+
+            if matches!(n % 8, 3 | 5) {
+                ret = -ret;
+            }
+        }
+
+        std::mem::swap(&mut a, &mut n);
+
+        if a % 4 == 3 && n % 4 == 3 {
+            ret = -ret;
+        }
+
+        a %= n;
+    }
+
+    if n == 1 {
+        ret
+    } else {
+        0
+    }
+}
+
+/// Whether `n` is a perfect square, building its root a bit at a time.
+fn is_square(n: &[u64]) -> bool {
+    let mut root = vec![0u64; n.len()];
+
+    for i in (0..bit_len(n).div_ceil(2)).rev() {
+        root[i as usize / 64] |= 1 << (i % 64);
+
+        if cmp(&mul(&root, &root), n).is_gt() {
+            root[i as usize / 64] &= !(1 << (i % 64));
+        }
+    }
+
+    cmp(&mul(&root, &root), n).is_eq()
+}
+
+fn mul(lhs: &[u64], rhs: &[u64]) -> Vec<u64> {
+    let mut out = vec![0u64; lhs.len() + rhs.len()];
+
+    for (i, &a) in lhs.iter().enumerate() {
+        let mut carry = 0u64;
+
+        for (j, &b) in rhs.iter().enumerate() {
+            let t = a as u128 * b as u128 + out[i + j] as u128 + carry as u128;
+            out[i + j] = t as u64;
+            carry = (t >> 64) as u64;
+        }
+
+        out[i + rhs.len()] = carry;
+    }
+
+    out
+}
+
+fn add_assign(lhs: &mut [u64], rhs: &[u64]) -> bool {
+    let mut carry = false;
+
+    for (i, word) in lhs.iter_mut().enumerate() {
+        let (sum, c1) = word.overflowing_add(rhs.get(i).copied().unwrap_or(0));
+        let (sum, c2) = sum.overflowing_add(carry as u64);
+        *word = sum;
+        carry = c1 || c2;
+    }
+
+    carry
+}
+
+fn sub_assign(lhs: &mut [u64], rhs: &[u64]) -> bool {
+    let mut borrow = false;
+
+    for (i, word) in lhs.iter_mut().enumerate() {
+        let (diff, b1) = word.overflowing_sub(rhs.get(i).copied().unwrap_or(0));
+        let (diff, b2) = diff.overflowing_sub(borrow as u64);
+        *word = diff;
+        borrow = b1 || b2;
+    }
+
+    borrow
+}
+
+/// `a + b mod n` for `a, b < n`.
+fn add_mod(a: &[u64], b: &[u64], n: &[u64]) -> Vec<u64> {
+    let mut ret = a.to_vec();
+
+    if add_assign(&mut ret, b) || cmp(&ret, n).is_ge() {
+        sub_assign(&mut ret, n);
+    }
+
+    ret
+}
+
+/// `a - b mod n` for `a, b < n`.
+fn sub_mod(a: &[u64], b: &[u64], n: &[u64]) -> Vec<u64> {
+    let mut ret = a.to_vec();
+
+    if sub_assign(&mut ret, b) {
+        add_assign(&mut ret, n);
+    }
+
+    ret
+}
+
+/// `a / 2 mod n` for odd `n` and `a < n`.
+fn half_mod(a: &[u64], n: &[u64]) -> Vec<u64> {
+    let mut ret = a.to_vec();
+    ret.push(0);
+
+    if ret[0] & 1 == 1 {
+        add_assign(&mut ret, n);
+    }
+
+    let mut ret = shr(&ret, 1);
+    ret.truncate(n.len());
+    ret
+}
+
+/// Compares little-endian word slices of possibly different lengths.
+fn cmp(lhs: &[u64], rhs: &[u64]) -> Ordering {
+    (0..lhs.len().max(rhs.len()))
+        .rev()
+        .map(|i| {
+            let a = lhs.get(i).copied().unwrap_or(0);
+            let b = rhs.get(i).copied().unwrap_or(0);
+
+            a.cmp(&b)
+        })
+        .find(|ord| ord.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+fn shr(words: &[u64], shift: u32) -> Vec<u64> {
+    (0..words.len())
+        .map(|i| {
+            let (idx, bits) = (i + shift as usize / 64, shift % 64);
+            let low = words.get(idx).copied().unwrap_or(0) >> bits;
+            let high = match bits {
+                0 => 0,
+                _ => words.get(idx + 1).copied().unwrap_or(0) << (64 - bits),
+            };
+
+            low | high
+        })
+        .collect()
+}
+
+fn rem_word(words: &[u64], den: u64) -> u64 {
+    words.iter().rev().fold(0, |rem, &word| {
+        (((rem as u128) << 64 | word as u128) % den as u128) as u64
+    })
+}
+
+fn word_vec(value: u64, len: usize) -> Vec<u64> {
+    let mut ret = vec![0; len];
+    ret[0] = value;
+    ret
+}
+
+fn bit(words: &[u64], idx: u32) -> bool {
+    words[idx as usize / 64] >> (idx % 64) & 1 == 1
+}
+
+fn bit_len(words: &[u64]) -> u32 {
+    match words.iter().rposition(|&word| word != 0) {
+        Some(idx) => 64 * idx as u32 + 64 - words[idx].leading_zeros(),
+        None => 0,
+    }
+}
+
+fn trailing_zeros(words: &[u64]) -> u32 {
+    let idx = words.iter().position(|&word| word != 0).unwrap();
+
+    64 * idx as u32 + words[idx].trailing_zeros()
+}
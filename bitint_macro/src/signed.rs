@@ -0,0 +1,872 @@
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    Fields, Index, ItemStruct, LitInt, Member, Path, Token,
+};
+
+use crate::{check_derives, limb_chunks, overflow_families};
+
+/// `#[bitint(bits)]` or `#[bitint(bits, path::to::unsigned_type)]`.
+pub struct Args {
+    bits: u32,
+    unsigned: Option<Path>,
+}
+
+impl Parse for Args {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let bits = input.parse::<LitInt>()?.base10_parse()?;
+        let unsigned = if input.parse::<Option<Token![,]>>()?.is_some() {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+
+        Ok(Args { bits, unsigned })
+    }
+}
+
+pub fn expand(args: Args, input: ItemStruct) -> syn::Result<TokenStream> {
+    let bits = args.bits;
+    assert!(
+        bits.is_multiple_of(8) && bits != 0,
+        "Bits need to be 8 aligned and non zero"
+    );
+
+    if let Fields::Unit = input.fields {
+    } else {
+        panic!("Expected unit struct");
+    }
+
+    check_derives(&input.attrs, &["PartialOrd", "Ord", "Debug"])?;
+
+    let name = input.ident.clone();
+    let unsigned = match args.unsigned {
+        Some(unsigned) => unsigned,
+        None => {
+            let signed = name.to_string();
+
+            match signed.strip_prefix('i') {
+                Some(rest) => Ident::new(&format!("u{}", rest), name.span()).into(),
+                None => return Err(syn::Error::new_spanned(
+                    &name,
+                    "cannot infer the unsigned type, pass it as `#[bitint(bits, unsigned_type)]`",
+                )),
+            }
+        }
+    };
+
+    let attrs = &input.attrs;
+    let vis = &input.vis;
+
+    let chunks = limb_chunks(bits);
+    let types: Vec<_> = chunks
+        .iter()
+        .map(|x| Ident::new(&format!("u{}", x), Span::call_site()))
+        .collect();
+    let top_idx = Member::Unnamed(Index {
+        index: chunks.len() as u32 - 1,
+        span: Span::call_site(),
+    });
+    let top_type = types.last().unwrap();
+    let low_types = &types[..types.len() - 1];
+    let sign_shift = chunks.last().unwrap() - 1;
+    let byte_count = bits as usize / 8;
+    // sign extension shift when narrowing into an i128
+    let i128_shift = 128u32.saturating_sub(bits);
+
+    let overflow_families_quote = overflow_families(&name, quote!());
+
+    let signed_prims = [quote!(i8), quote!(i16), quote!(i32), quote!(i64)];
+    let unsigned_prims = [quote!(u8), quote!(u16), quote!(u32), quote!(u64)];
+
+    let radix_traits = [
+        quote!(LowerHex),
+        quote!(UpperHex),
+        quote!(Binary),
+        quote!(Octal),
+    ];
+
+    let binary_ops = [
+        (
+            quote!(Add),
+            quote!(add),
+            quote!(AddAssign),
+            quote!(add_assign),
+            quote!(+),
+        ),
+        (
+            quote!(Sub),
+            quote!(sub),
+            quote!(SubAssign),
+            quote!(sub_assign),
+            quote!(-),
+        ),
+        (
+            quote!(Mul),
+            quote!(mul),
+            quote!(MulAssign),
+            quote!(mul_assign),
+            quote!(*),
+        ),
+        (
+            quote!(Div),
+            quote!(div),
+            quote!(DivAssign),
+            quote!(div_assign),
+            quote!(/),
+        ),
+        (
+            quote!(Rem),
+            quote!(rem),
+            quote!(RemAssign),
+            quote!(rem_assign),
+            quote!(%),
+        ),
+    ];
+
+    let bit_ops = [
+        (
+            quote!(BitAnd),
+            quote!(bitand),
+            quote!(BitAndAssign),
+            quote!(bitand_assign),
+            quote!(&),
+        ),
+        (
+            quote!(BitOr),
+            quote!(bitor),
+            quote!(BitOrAssign),
+            quote!(bitor_assign),
+            quote!(|),
+        ),
+        (
+            quote!(BitXor),
+            quote!(bitxor),
+            quote!(BitXorAssign),
+            quote!(bitxor_assign),
+            quote!(^),
+        ),
+    ]
+    .iter()
+    .map(|(trait_, method, assign_trait, assign_method, op)| {
+        quote! {
+            impl std::ops::#trait_ for #name {
+                type Output = #name;
+
+                #[inline]
+                fn #method(self, other: #name) -> #name {
+                    #name(self.0 #op other.0)
+                }
+            }
+
+            impl std::ops::#assign_trait for #name {
+                #[inline]
+                fn #assign_method(&mut self, other: #name) {
+                    *self = *self #op other;
+                }
+            }
+        }
+    })
+    .collect::<Vec<_>>();
+
+    let ref_ops = binary_ops
+        .iter()
+        .map(|(trait_, method, assign_trait, assign_method, op)| {
+            quote! {
+                impl std::ops::#trait_<&#name> for &#name {
+                    type Output = #name;
+
+                    #[inline]
+                    fn #method(self, other: &#name) -> #name {
+                        *self #op *other
+                    }
+                }
+
+                impl std::ops::#trait_<&#name> for #name {
+                    type Output = #name;
+
+                    #[inline]
+                    fn #method(self, other: &#name) -> #name {
+                        self #op *other
+                    }
+                }
+
+                impl<'a> std::ops::#trait_<#name> for &'a #name {
+                    type Output = #name;
+
+                    #[inline]
+                    fn #method(self, other: #name) -> #name {
+                        *self #op other
+                    }
+                }
+
+                impl std::ops::#assign_trait for #name {
+                    #[inline]
+                    fn #assign_method(&mut self, other: #name) {
+                        *self = *self #op other;
+                    }
+                }
+
+                impl std::ops::#assign_trait<&#name> for #name {
+                    #[inline]
+                    fn #assign_method(&mut self, other: &#name) {
+                        *self = *self #op *other;
+                    }
+                }
+            }
+        });
+
+    Ok(quote! {
+        #(#attrs)*
+        #vis struct #name(pub #unsigned);
+
+        impl #name {
+            /// The smallest value, `-2^(BITS - 1)`.
+            pub const MIN: #name = #name(#unsigned(#(#low_types::MIN,)* 1 << #sign_shift));
+            /// The largest value, `2^(BITS - 1) - 1`.
+            pub const MAX: #name = #name(#unsigned(#(#low_types::MAX,)* #top_type::MAX >> 1));
+            pub const ZERO: #name = #name(#unsigned::ZERO);
+            pub const ONE: #name = #name(#unsigned::ONE);
+            /// Size of the type in bits.
+            pub const BITS: u32 = #bits;
+            /// Size of the type in bytes, as taken and returned by the byte conversions.
+            pub const BYTES: usize = #byte_count;
+
+            /// Reinterprets the two's complement bits as the unsigned type.
+            #[inline]
+            pub const fn cast_unsigned(self) -> #unsigned {
+                self.0
+            }
+
+            /// Reinterprets the bits of the unsigned type as two's complement.
+            #[inline]
+            pub const fn cast_signed(value: #unsigned) -> #name {
+                #name(value)
+            }
+
+            #[inline]
+            pub const fn is_negative(self) -> bool {
+                self.0.#top_idx >> #sign_shift == 1
+            }
+
+            #[inline]
+            pub fn is_positive(self) -> bool {
+                !self.is_negative() && self.0 != #unsigned::ZERO
+            }
+
+            /// Returns `-1`, `0` or `1` depending on the sign of `self`.
+            pub fn signum(self) -> #name {
+                if self.is_negative() {
+                    -#name::ONE
+                } else if self.0 == #unsigned::ZERO {
+                    #name::ZERO
+                } else {
+                    #name::ONE
+                }
+            }
+
+            pub fn overflowing_neg(self) -> (#name, bool) {
+                let (ret, _) = #unsigned::ZERO.overflowing_sub(self.0);
+
+                (#name(ret), self == #name::MIN)
+            }
+
+            pub fn overflowing_abs(self) -> (#name, bool) {
+                if self.is_negative() {
+                    self.overflowing_neg()
+                } else {
+                    (self, false)
+                }
+            }
+
+            #[inline]
+            pub fn abs(self) -> #name {
+                let (ret, overflow) = self.overflowing_abs();
+
+                debug_assert!(!overflow, "attempt to negate with overflow");
+
+                ret
+            }
+
+            /// Absolute value as the unsigned type, which cannot overflow.
+            #[inline]
+            pub fn unsigned_abs(self) -> #unsigned {
+                self.overflowing_abs().0.cast_unsigned()
+            }
+
+            pub fn overflowing_add(self, rhs: #name) -> (#name, bool) {
+                let ret = #name(self.0.overflowing_add(rhs.0).0);
+
+                (
+                    ret,
+                    self.is_negative() == rhs.is_negative()
+                        && ret.is_negative() != self.is_negative(),
+                )
+            }
+
+            pub fn overflowing_sub(self, rhs: #name) -> (#name, bool) {
+                let ret = #name(self.0.overflowing_sub(rhs.0).0);
+
+                (
+                    ret,
+                    self.is_negative() != rhs.is_negative()
+                        && ret.is_negative() != self.is_negative(),
+                )
+            }
+
+            pub fn overflowing_mul(self, rhs: #name) -> (#name, bool) {
+                let ret = #name(self.0.overflowing_mul(rhs.0).0);
+                let (magnitude, overflow) = self.unsigned_abs().overflowing_mul(rhs.unsigned_abs());
+                let limit = #name::MIN.0;
+
+                let overflow = overflow
+                    || if self.is_negative() != rhs.is_negative() {
+                        magnitude > limit
+                    } else {
+                        magnitude >= limit
+                    };
+
+                (ret, overflow)
+            }
+
+            /// Shifts left by `rhs % BITS` bits, reporting whether `rhs` was out of range.
+            #[inline]
+            pub fn overflowing_shl(self, rhs: u32) -> (#name, bool) {
+                let (ret, overflow) = self.0.overflowing_shl(rhs);
+
+                (#name(ret), overflow)
+            }
+
+            /// Arithmetic shift right by `rhs % BITS` bits, reporting whether `rhs` was out
+            /// of range.
+            #[inline]
+            pub fn overflowing_shr(self, rhs: u32) -> (#name, bool) {
+                if self.is_negative() {
+                    let (ret, overflow) = (!self.0).overflowing_shr(rhs);
+
+                    (#name(!ret), overflow)
+                } else {
+                    let (ret, overflow) = self.0.overflowing_shr(rhs);
+
+                    (#name(ret), overflow)
+                }
+            }
+
+            #overflow_families_quote
+
+            #[inline]
+            pub fn saturating_add(self, rhs: #name) -> #name {
+                match self.overflowing_add(rhs) {
+                    (ret, false) => ret,
+                    _ if rhs.is_negative() => #name::MIN,
+                    _ => #name::MAX,
+                }
+            }
+
+            #[inline]
+            pub fn saturating_sub(self, rhs: #name) -> #name {
+                match self.overflowing_sub(rhs) {
+                    (ret, false) => ret,
+                    _ if rhs.is_negative() => #name::MAX,
+                    _ => #name::MIN,
+                }
+            }
+
+            #[inline]
+            pub fn saturating_mul(self, rhs: #name) -> #name {
+                match self.overflowing_mul(rhs) {
+                    (ret, false) => ret,
+                    _ if self.is_negative() != rhs.is_negative() => #name::MIN,
+                    _ => #name::MAX,
+                }
+            }
+
+            /// Saturates the single overflowing case `MIN / -1` to `MAX`.
+            #[inline]
+            pub fn saturating_div(self, rhs: #name) -> #name {
+                match self.overflowing_div(rhs) {
+                    (ret, false) => ret,
+                    _ => #name::MAX,
+                }
+            }
+
+            #[inline]
+            pub fn saturating_pow(self, exp: u32) -> #name {
+                match self.overflowing_pow(exp) {
+                    (ret, false) => ret,
+                    _ if self.is_negative() && exp % 2 == 1 => #name::MIN,
+                    _ => #name::MAX,
+                }
+            }
+
+            pub fn overflowing_div(self, rhs: #name) -> (#name, bool) {
+                if rhs.0 == #unsigned::ZERO {
+                    panic!("attempt to divide by zero");
+                }
+
+                let (quot, _, overflow) = self.div_rem_inner(rhs);
+
+                (quot, overflow)
+            }
+
+            pub fn overflowing_rem(self, rhs: #name) -> (#name, bool) {
+                if rhs.0 == #unsigned::ZERO {
+                    panic!("attempt to calculate the remainder with a divisor of zero");
+                }
+
+                let (_, rem, overflow) = self.div_rem_inner(rhs);
+
+                (rem, overflow)
+            }
+
+            /// Returns the quotient (rounded towards zero) and remainder of `self / rhs`.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `rhs` is zero or on `MIN / -1` overflow in debug builds.
+            pub fn div_rem(self, rhs: #name) -> (#name, #name) {
+                match self.checked_div_rem(rhs) {
+                    Some(ret) => ret,
+                    None if rhs.0 == #unsigned::ZERO => panic!("attempt to divide by zero"),
+                    None => panic!("attempt to divide with overflow"),
+                }
+            }
+
+            /// Returns the quotient and remainder of `self / rhs`, or `None` if `rhs` is zero
+            /// or the division overflows.
+            pub fn checked_div_rem(self, rhs: #name) -> Option<(#name, #name)> {
+                if rhs.0 == #unsigned::ZERO {
+                    return None;
+                }
+
+                match self.div_rem_inner(rhs) {
+                    (_, _, true) => None,
+                    (quot, rem, false) => Some((quot, rem)),
+                }
+            }
+
+            fn div_rem_inner(self, rhs: #name) -> (#name, #name, bool) {
+                let (quot, rem) = self.unsigned_abs().div_rem(rhs.unsigned_abs());
+                // a negative quotient has magnitude at most 2^(BITS - 1), which always fits
+                let (quot, overflow) = if self.is_negative() != rhs.is_negative() {
+                    (#name(quot).overflowing_neg().0, false)
+                } else {
+                    (#name(quot), quot > #name::MAX.0)
+                };
+                let rem = if self.is_negative() {
+                    #name(rem).overflowing_neg().0
+                } else {
+                    #name(rem)
+                };
+
+                (quot, rem, overflow)
+            }
+
+            /// Parses an optionally `+` or `-` prefixed string of digits in the given radix.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `radix` is not in the range `2..=36`.
+            pub fn from_str_radix(
+                src: &str,
+                radix: u32,
+            ) -> Result<#name, ::librypt_int::ParseIntError> {
+                match src.strip_prefix('-') {
+                    Some(digits) => #name::from_magnitude(true, digits, radix),
+                    None => #name::from_magnitude(false, src, radix),
+                }
+            }
+
+            fn from_magnitude(
+                negative: bool,
+                digits: &str,
+                radix: u32,
+            ) -> Result<#name, ::librypt_int::ParseIntError> {
+                if negative && (digits.is_empty() || digits.starts_with('+')) {
+                    return Err(::librypt_int::ParseIntError::InvalidDigit);
+                }
+
+                let magnitude = #unsigned::from_str_radix(digits, radix)?;
+
+                if negative {
+                    if magnitude > #name::MIN.0 {
+                        return Err(::librypt_int::ParseIntError::Overflow);
+                    }
+
+                    Ok(#name(magnitude).overflowing_neg().0)
+                } else if magnitude > #name::MAX.0 {
+                    Err(::librypt_int::ParseIntError::Overflow)
+                } else {
+                    Ok(#name(magnitude))
+                }
+            }
+
+            #[inline]
+            pub fn from_le_bytes(bytes: [u8; #byte_count]) -> #name {
+                #name(#unsigned::from_le_bytes(bytes))
+            }
+
+            #[inline]
+            pub fn from_be_bytes(bytes: [u8; #byte_count]) -> #name {
+                #name(#unsigned::from_be_bytes(bytes))
+            }
+
+            #[inline]
+            pub fn from_ne_bytes(bytes: [u8; #byte_count]) -> #name {
+                #name(#unsigned::from_ne_bytes(bytes))
+            }
+
+            #[inline]
+            pub fn to_le_bytes(self) -> [u8; #byte_count] {
+                self.0.to_le_bytes()
+            }
+
+            #[inline]
+            pub fn to_be_bytes(self) -> [u8; #byte_count] {
+                self.0.to_be_bytes()
+            }
+
+            #[inline]
+            pub fn to_ne_bytes(self) -> [u8; #byte_count] {
+                self.0.to_ne_bytes()
+            }
+        }
+
+        impl std::ops::Add for #name {
+            type Output = #name;
+
+            #[inline]
+            fn add(self, other: #name) -> #name {
+                let (ret, overflow) = self.overflowing_add(other);
+
+                debug_assert!(!overflow, "attempt to add with overflow");
+
+                ret
+            }
+        }
+
+        impl std::ops::Sub for #name {
+            type Output = #name;
+
+            #[inline]
+            fn sub(self, other: #name) -> #name {
+                let (ret, overflow) = self.overflowing_sub(other);
+
+                debug_assert!(!overflow, "attempt to subtract with overflow");
+
+                ret
+            }
+        }
+
+        impl std::ops::Mul for #name {
+            type Output = #name;
+
+            #[inline]
+            fn mul(self, other: #name) -> #name {
+                let (ret, overflow) = self.overflowing_mul(other);
+
+                debug_assert!(!overflow, "attempt to multiply with overflow");
+
+                ret
+            }
+        }
+
+        impl std::ops::Div for #name {
+            type Output = #name;
+
+            #[inline]
+            fn div(self, other: #name) -> #name {
+                let (ret, overflow) = self.overflowing_div(other);
+
+                debug_assert!(!overflow, "attempt to divide with overflow");
+
+                ret
+            }
+        }
+
+        impl std::ops::Rem for #name {
+            type Output = #name;
+
+            #[inline]
+            fn rem(self, other: #name) -> #name {
+                let (ret, overflow) = self.overflowing_rem(other);
+
+                debug_assert!(
+                    !overflow,
+                    "attempt to calculate the remainder with overflow"
+                );
+
+                ret
+            }
+        }
+
+        #(#ref_ops)*
+
+        impl std::ops::Neg for #name {
+            type Output = #name;
+
+            #[inline]
+            fn neg(self) -> #name {
+                let (ret, overflow) = self.overflowing_neg();
+
+                debug_assert!(!overflow, "attempt to negate with overflow");
+
+                ret
+            }
+        }
+
+        impl std::ops::Neg for &#name {
+            type Output = #name;
+
+            #[inline]
+            fn neg(self) -> #name {
+                -*self
+            }
+        }
+
+        impl<T: ::librypt_int::ShiftAmount> std::ops::Shr<T> for #name {
+            type Output = #name;
+
+            /// Arithmetic shift by `rhs mod BITS` bits, filling with copies of the sign
+            /// bit and panicking in debug builds if `rhs` is out of range.
+            #[inline]
+            fn shr(self, rhs: T) -> #name {
+                let (rhs, overflow) = rhs.reduce(#bits);
+
+                debug_assert!(!overflow, "attempt to shift right with overflow");
+
+                self.overflowing_shr(rhs).0
+            }
+        }
+
+        impl<T: ::librypt_int::ShiftAmount> std::ops::Shr<T> for &#name {
+            type Output = #name;
+
+            #[inline]
+            fn shr(self, rhs: T) -> #name {
+                *self >> rhs
+            }
+        }
+
+        impl<T: ::librypt_int::ShiftAmount> std::ops::ShrAssign<T> for #name {
+            #[inline]
+            fn shr_assign(&mut self, rhs: T) {
+                *self = *self >> rhs;
+            }
+        }
+
+        impl<T: ::librypt_int::ShiftAmount> std::ops::Shl<T> for #name {
+            type Output = #name;
+
+            /// Shifts by `rhs mod BITS` bits, panicking in debug builds if `rhs` is out
+            /// of range.
+            #[inline]
+            fn shl(self, rhs: T) -> #name {
+                let (rhs, overflow) = rhs.reduce(#bits);
+
+                debug_assert!(!overflow, "attempt to shift left with overflow");
+
+                self.overflowing_shl(rhs).0
+            }
+        }
+
+        impl<T: ::librypt_int::ShiftAmount> std::ops::Shl<T> for &#name {
+            type Output = #name;
+
+            #[inline]
+            fn shl(self, rhs: T) -> #name {
+                *self << rhs
+            }
+        }
+
+        impl<T: ::librypt_int::ShiftAmount> std::ops::ShlAssign<T> for #name {
+            #[inline]
+            fn shl_assign(&mut self, rhs: T) {
+                *self = *self << rhs;
+            }
+        }
+
+        impl std::ops::Not for #name {
+            type Output = #name;
+
+            #[inline]
+            fn not(self) -> #name {
+                #name(!self.0)
+            }
+        }
+
+        #(#bit_ops)*
+
+        impl Ord for #name {
+            fn cmp(&self, other: &#name) -> std::cmp::Ordering {
+                match (self.is_negative(), other.is_negative()) {
+                    (true, false) => std::cmp::Ordering::Less,
+                    (false, true) => std::cmp::Ordering::Greater,
+                    _ => self.0.cmp(&other.0),
+                }
+            }
+        }
+
+        impl PartialOrd for #name {
+            #[inline]
+            fn partial_cmp(&self, other: &#name) -> Option<std::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl std::str::FromStr for #name {
+            type Err = ::librypt_int::ParseIntError;
+
+            /// Parses an optionally signed decimal string, or hexadecimal, octal and binary
+            /// magnitudes prefixed with `0x`, `0o` or `0b`.
+            fn from_str(src: &str) -> Result<#name, Self::Err> {
+                if src.is_empty() {
+                    return Err(::librypt_int::ParseIntError::Empty);
+                }
+
+                let (negative, rest) = match src.strip_prefix('-') {
+                    // split_prefix takes a `+` of its own, so rule out `-+` here
+                    Some(rest) if rest.is_empty() || rest.starts_with('+') => {
+                        return Err(::librypt_int::ParseIntError::InvalidDigit);
+                    }
+                    Some(rest) => (true, rest),
+                    None => (false, src),
+                };
+                let (digits, radix) = ::librypt_int::radix::split_prefix(rest)?;
+
+                #name::from_magnitude(negative, digits, radix)
+            }
+        }
+
+        impl std::fmt::Display for #name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.pad_integral(!self.is_negative(), "", &self.unsigned_abs().to_string())
+            }
+        }
+
+        impl std::fmt::Debug for #name {
+            #[inline]
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                // `{:x?}` and `{:X?}` set these bits, which have no stable accessor
+                #[allow(deprecated)]
+                let flags = f.flags();
+
+                if flags & (1 << 4) != 0 {
+                    std::fmt::LowerHex::fmt(self, f)
+                } else if flags & (1 << 5) != 0 {
+                    std::fmt::UpperHex::fmt(self, f)
+                } else {
+                    std::fmt::Display::fmt(self, f)
+                }
+            }
+        }
+
+        // radix formatting shows the two's complement bits, like the primitive types
+        #(
+            impl std::fmt::#radix_traits for #name {
+                #[inline]
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    std::fmt::#radix_traits::fmt(&self.0, f)
+                }
+            }
+        )*
+
+        impl Default for #name {
+            #[inline]
+            fn default() -> #name {
+                #name::ZERO
+            }
+        }
+
+        #(
+            impl From<#unsigned_prims> for #name {
+                #[inline]
+                fn from(value: #unsigned_prims) -> #name {
+                    #name(#unsigned::from(value))
+                }
+            }
+
+            impl From<#signed_prims> for #name {
+                #[inline]
+                fn from(value: #signed_prims) -> #name {
+                    #name::from(value as i128)
+                }
+            }
+
+            impl From<#name> for #unsigned_prims {
+                #[inline]
+                fn from(value: #name) -> #unsigned_prims {
+                    i128::from(value) as #unsigned_prims
+                }
+            }
+
+            impl From<#name> for #signed_prims {
+                #[inline]
+                fn from(value: #name) -> #signed_prims {
+                    i128::from(value) as #signed_prims
+                }
+            }
+        )*
+
+        impl From<u128> for #name {
+            #[inline]
+            fn from(value: u128) -> #name {
+                #name(#unsigned::from(value))
+            }
+        }
+
+        impl From<i128> for #name {
+            #[inline]
+            fn from(value: i128) -> #name {
+                let ret = #name(#unsigned::from(value.unsigned_abs()));
+
+                if value < 0 {
+                    ret.overflowing_neg().0
+                } else {
+                    ret
+                }
+            }
+        }
+
+        impl From<#name> for i128 {
+            #[inline]
+            fn from(value: #name) -> i128 {
+                (u128::from(value.0) << #i128_shift) as i128 >> #i128_shift
+            }
+        }
+
+        impl From<#name> for u128 {
+            #[inline]
+            fn from(value: #name) -> u128 {
+                i128::from(value) as u128
+            }
+        }
+
+        impl TryFrom<#unsigned> for #name {
+            type Error = ::librypt_int::TryFromIntError;
+
+            #[inline]
+            fn try_from(value: #unsigned) -> Result<#name, Self::Error> {
+                if #name(value).is_negative() {
+                    Err(::librypt_int::TryFromIntError)
+                } else {
+                    Ok(#name(value))
+                }
+            }
+        }
+
+        impl TryFrom<#name> for #unsigned {
+            type Error = ::librypt_int::TryFromIntError;
+
+            #[inline]
+            fn try_from(value: #name) -> Result<#unsigned, Self::Error> {
+                if value.is_negative() {
+                    Err(::librypt_int::TryFromIntError)
+                } else {
+                    Ok(value.0)
+                }
+            }
+        }
+    })
+}
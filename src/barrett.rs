@@ -0,0 +1,214 @@
+//! Barrett reduction modulo a fixed modulus.
+
+use crate::{ct, limbs, pow, BitUint, Choice};
+
+/// Precomputed context for arithmetic modulo any non-zero `modulus`, including
+/// even ones where [`Montgomery`](crate::Montgomery) does not apply.
+///
+/// With `k` the bit length of the modulus, a value below `4^k` is reduced with
+/// two multiplications by `mu = floor(4^k / modulus)` and the modulus instead
+/// of a division. Every reduction performs the same operations, so it takes the
+/// same time for every value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Barrett<T: BitUint> {
+    modulus: T,
+    k: u32,
+    /// `floor((4^k - 1) / modulus) - 2^k`, which always fits `T`
+    mu: T,
+}
+
+/// Most multiples of the modulus the quotient estimate can fall short by: the
+/// floors of `x / 2^k` and `mu` cost under `2^k / modulus <= 2` and `1`, and
+/// the final floor one more.
+const CORRECTIONS: usize = 4;
+
+impl<T: BitUint> Barrett<T> {
+    /// Builds the context, returning `None` if `modulus` is zero.
+    pub fn new(modulus: T) -> Option<Barrett<T>> {
+        let m = modulus.to_words();
+        let m = m.as_ref();
+        let top = m.iter().rposition(|&word| word != 0)?;
+        let k = 64 * top as u32 + 64 - m[top].leading_zeros();
+
+        // binary long division of 4^k - 1, which is 2k one bits
+        let mut rem = T::ZERO.to_words();
+        let mut quot = T::ZERO.to_words();
+
+        for _ in 0..2 * k {
+            let (r, q) = (rem.as_mut(), quot.as_mut());
+            let carry = r[r.len() - 1] >> 63;
+
+            limbs::shl_assign(r, 1);
+            r[0] |= 1;
+            limbs::shl_assign(q, 1);
+
+            if carry == 1 || !bool::from(ct::lt(r, m)) {
+                limbs::sub_assign(r, m);
+                q[0] |= 1;
+            }
+        }
+
+        // the quotient has exactly k + 1 bits, drop the top one
+        let q = quot.as_mut();
+        if let Some(word) = q.get_mut(k as usize / 64) {
+            *word &= !(1 << (k % 64));
+        }
+
+        Some(Barrett {
+            modulus,
+            k,
+            mu: T::from_words(quot),
+        })
+    }
+
+    #[inline]
+    pub const fn modulus(&self) -> T {
+        self.modulus
+    }
+
+    /// Reduces `high * 2^BITS + low`, as returned by `widening_mul`, which must be
+    /// below `4^k` as any product of two reduced values is.
+    pub fn reduce(&self, low: T, high: T) -> T {
+        let mut wide = low.widening_mul_words(T::ONE);
+        let mut top = high.widening_mul_words(T::ONE);
+
+        limbs::shl_assign(top.as_mut(), T::BITS);
+        limbs::add_assign(wide.as_mut(), top.as_ref());
+
+        self.reduce_words(wide)
+    }
+
+    /// Reduces a full product, as returned by `widening_mul_words`, which must be
+    /// below `4^k`.
+    pub fn reduce_words(&self, wide: T::WideWords) -> T {
+        let n = self.modulus.to_words().as_ref().len();
+
+        // q1 = x / 2^k fits T since x < 4^k
+        let mut shifted = wide;
+        limbs::shr_assign(shifted.as_mut(), self.k);
+        debug_assert!(
+            shifted.as_ref()[n..].iter().all(|&word| word == 0),
+            "value not below 4^k"
+        );
+
+        let mut q1 = self.modulus.to_words();
+        q1.as_mut().copy_from_slice(&shifted.as_ref()[..n]);
+        let q1 = T::from_words(q1);
+
+        // q1 * (2^k + mu) / 2^k = q1 + q1 * mu / 2^k, so the estimate is q1 + z
+        let mut z = q1.widening_mul_words(self.mu);
+        limbs::shr_assign(z.as_mut(), self.k);
+
+        let mut words = self.modulus.to_words();
+        words.as_mut().copy_from_slice(&z.as_ref()[..n]);
+        let z = T::from_words(words);
+
+        let mut rem = wide;
+        limbs::sub_assign(rem.as_mut(), q1.widening_mul_words(self.modulus).as_ref());
+        limbs::sub_assign(rem.as_mut(), z.widening_mul_words(self.modulus).as_ref());
+
+        let modulus = self.modulus.widening_mul_words(T::ONE);
+
+        for _ in 0..CORRECTIONS {
+            let mut diff = rem;
+            let borrow = limbs::sub_assign(diff.as_mut(), modulus.as_ref());
+
+            ct::assign(rem.as_mut(), diff.as_ref(), Choice::from(!borrow as u8));
+        }
+
+        let mut ret = self.modulus.to_words();
+        ret.as_mut().copy_from_slice(&rem.as_ref()[..n]);
+
+        T::from_words(ret)
+    }
+
+    /// `a * b mod modulus`, for operands below the modulus.
+    #[inline]
+    pub fn mul(&self, a: T, b: T) -> T {
+        self.reduce_words(a.widening_mul_words(b))
+    }
+
+    #[inline]
+    pub fn square(&self, a: T) -> T {
+        self.mul(a, a)
+    }
+
+    /// Raises `base`, below the modulus, to `exp` with a sliding window, in
+    /// variable time.
+    pub fn pow(&self, base: T, exp: T) -> T {
+        pow::sliding_window(self.one(), base, exp, |a, b| self.mul(a, b))
+    }
+
+    /// Raises `base`, below the modulus, to `exp` with a Montgomery ladder, in
+    /// constant time.
+    pub fn pow_ct(&self, base: T, exp: T) -> T {
+        pow::ladder(self.one(), base, exp, |a, b| self.mul(a, b))
+    }
+
+    /// One, or zero modulo one.
+    fn one(&self) -> T {
+        if self.modulus == T::ONE {
+            T::ZERO
+        } else {
+            T::ONE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::limbs::xorshift;
+    use crate::{u24, u256, Barrett};
+
+    #[test]
+    fn barrett() {
+        assert!(Barrett::new(u256::ZERO).is_none());
+
+        let mut rng = xorshift(0x9e37_79b9_7f4a_7c15);
+        let mut next = || u256::from_le_bytes([0u8; 32].map(|_| rng() as u8));
+
+        // even, full width, power of two and short moduli
+        let moduli = [
+            next() & !u256::ONE,
+            u256::MAX - u256::ONE,
+            u256::ONE << 255,
+            u256::ONE << 100,
+            u256::from(1_000_000_006u64),
+            u256::from(3),
+            u256::ONE,
+        ];
+
+        for m in moduli {
+            let ctx = Barrett::new(m).unwrap();
+            let top = m - u256::ONE;
+
+            assert_eq!(ctx.mul(top, top), top.mul_mod(top, m));
+            assert_eq!(ctx.square(u256::ZERO), u256::ZERO);
+
+            for _ in 0..100 {
+                let (a, b) = (next() % m, next() % m);
+                assert_eq!(ctx.mul(a, b), a.mul_mod(b, m));
+            }
+
+            let (a, e) = (next() % m, next());
+            assert_eq!(ctx.pow(a, e), a.pow_mod(e, m));
+            assert_eq!(ctx.pow_ct(a, e), a.pow_mod(e, m));
+        }
+
+        // largest input below 4^k, 2^262 - 1 for a 131 bit modulus
+        let m = u256::from(u128::MAX) * u256::from(6);
+        let ctx = Barrett::new(m).unwrap();
+        let half = (u256::ONE << 131) % m;
+        assert_eq!(
+            ctx.reduce(u256::MAX, u256::from(63)),
+            half.mul_mod(half, m).sub_mod(u256::ONE, m)
+        );
+
+        let ctx24 = Barrett::new(u24::from(1 << 12)).unwrap();
+        assert_eq!(
+            ctx24.reduce(u24::from(0xff_ffff), u24::ZERO),
+            u24::from(0xfff)
+        );
+        assert_eq!(ctx.modulus(), m);
+    }
+}
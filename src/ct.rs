@@ -0,0 +1,280 @@
+//! Constant time primitives behind the `ct_*` methods of the generated types.
+//!
+//! Everything here runs in time independent of the word values: there are no
+//! data dependent branches, indexes or early exits.
+
+use std::hint::black_box;
+use std::ops::{BitAnd, BitOr, BitXor, Not};
+
+/// A constant time boolean, either `0` or `1`.
+///
+/// Unlike `bool` the compiler is kept from turning operations on it back into
+/// branches. Convert it into a `bool` only once the value is no longer secret.
+#[derive(Clone, Copy, Debug)]
+pub struct Choice(u8);
+
+impl Choice {
+    #[inline]
+    pub fn unwrap_u8(self) -> u8 {
+        self.0
+    }
+
+    /// All ones when set, all zeros otherwise.
+    #[inline]
+    pub(crate) fn mask(self) -> u64 {
+        0u64.wrapping_sub(self.0 as u64)
+    }
+}
+
+impl From<u8> for Choice {
+    /// `value` must be `0` or `1`.
+    #[inline]
+    fn from(value: u8) -> Choice {
+        debug_assert!(value <= 1);
+
+        Choice(black_box(value))
+    }
+}
+
+impl From<Choice> for bool {
+    #[inline]
+    fn from(choice: Choice) -> bool {
+        choice.0 != 0
+    }
+}
+
+impl BitAnd for Choice {
+    type Output = Choice;
+
+    #[inline]
+    fn bitand(self, rhs: Choice) -> Choice {
+        Choice::from(self.0 & rhs.0)
+    }
+}
+
+impl BitOr for Choice {
+    type Output = Choice;
+
+    #[inline]
+    fn bitor(self, rhs: Choice) -> Choice {
+        Choice::from(self.0 | rhs.0)
+    }
+}
+
+impl BitXor for Choice {
+    type Output = Choice;
+
+    #[inline]
+    fn bitxor(self, rhs: Choice) -> Choice {
+        Choice::from(self.0 ^ rhs.0)
+    }
+}
+
+impl Not for Choice {
+    type Output = Choice;
+
+    #[inline]
+    fn not(self) -> Choice {
+        Choice::from(self.0 ^ 1)
+    }
+}
+
+/// `1` if `word` is zero.
+#[inline]
+fn is_zero(word: u64) -> Choice {
+    Choice::from((((word | word.wrapping_neg()) >> 63) ^ 1) as u8)
+}
+
+#[doc(hidden)]
+pub fn eq(lhs: &[u64], rhs: &[u64]) -> Choice {
+    let diff = lhs.iter().zip(rhs).fold(0, |acc, (a, b)| acc | (a ^ b));
+
+    is_zero(diff)
+}
+
+#[doc(hidden)]
+pub fn lt(lhs: &[u64], rhs: &[u64]) -> Choice {
+    let mut borrow = 0u64;
+
+    for (&a, &b) in lhs.iter().zip(rhs) {
+        let diff = (a as u128).wrapping_sub(b as u128 + borrow as u128);
+        borrow = (diff >> 64) as u64 & 1;
+    }
+
+    Choice::from(borrow as u8)
+}
+
+/// Writes `rhs` into `lhs` if `choice` is set, leaving it untouched otherwise.
+#[doc(hidden)]
+pub fn assign(lhs: &mut [u64], rhs: &[u64], choice: Choice) {
+    let mask = choice.mask();
+
+    for (a, &b) in lhs.iter_mut().zip(rhs) {
+        *a ^= mask & (*a ^ b);
+    }
+}
+
+/// Swaps `lhs` and `rhs` if `choice` is set.
+#[doc(hidden)]
+pub fn swap(lhs: &mut [u64], rhs: &mut [u64], choice: Choice) {
+    let mask = choice.mask();
+
+    for (a, b) in lhs.iter_mut().zip(rhs.iter_mut()) {
+        let t = mask & (*a ^ *b);
+        *a ^= t;
+        *b ^= t;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::limbs::xorshift;
+    use crate::{u2048, u4096, Barrett, BitUint, Montgomery, Solinas};
+    use std::time::Instant;
+
+    type Op<'a, T> = Box<dyn FnMut(T) -> T + 'a>;
+
+    /// Welch's t statistic between the timings of two input classes, in the style
+    /// of dudect. Inputs of both classes are interleaved pseudo randomly so drift
+    /// in the machine state affects both equally.
+    fn leakage<T: Copy>(rounds: usize, mut op: impl FnMut(T) -> T, fixed: T, random: &[T]) -> f64 {
+        const BATCH: usize = 8;
+
+        let mut samples = [vec![], vec![]];
+        let mut next = xorshift(0x9e37_79b9_7f4a_7c15);
+
+        for round in 0..rounds {
+            let class = (next() & 1) as usize;
+            let input = if class == 0 {
+                fixed
+            } else {
+                random[round % random.len()]
+            };
+
+            let start = Instant::now();
+            for _ in 0..BATCH {
+                std::hint::black_box(op(std::hint::black_box(input)));
+            }
+            samples[class].push(start.elapsed().as_nanos() as f64);
+        }
+
+        // discard the slowest tail, which is dominated by interrupts
+        let stats = samples.map(|mut class| {
+            class.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            class.truncate(class.len() * 9 / 10);
+
+            let n = class.len() as f64;
+            let mean = class.iter().sum::<f64>() / n;
+            let var = class.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+
+            (n, mean, var)
+        });
+
+        let [(n0, m0, v0), (n1, m1, v1)] = stats;
+
+        (m0 - m1) / (v0 / n0 + v1 / n1).sqrt()
+    }
+
+    fn random_inputs<T: BitUint>() -> Vec<T> {
+        let mut next = xorshift(0x2545_f491_4f6c_dd1d);
+
+        (0..64)
+            .map(|_| {
+                let mut words = T::ZERO.to_words();
+                words.as_mut().fill_with(&mut next);
+
+                T::from_words(words)
+            })
+            .collect()
+    }
+
+    // Timing based and therefore noisy, run with
+    // `cargo test --release -- --ignored constant_time`.
+    #[test]
+    #[ignore]
+    fn constant_time_ops_do_not_leak() {
+        let random = random_inputs::<u4096>();
+        let secret = random[0];
+        let threshold = 10.0;
+
+        let ops: [(&str, Op<u4096>); 7] = [
+            (
+                "ct_eq",
+                Box::new(|x| u4096::from(x.ct_eq(&secret).unwrap_u8())),
+            ),
+            (
+                "ct_lt",
+                Box::new(|x| u4096::from(x.ct_lt(&secret).unwrap_u8())),
+            ),
+            ("ct_add", Box::new(|x| x.ct_add(secret).0)),
+            ("ct_sub", Box::new(|x| x.ct_sub(secret).0)),
+            ("ct_mul", Box::new(|x| x.ct_mul(secret))),
+            (
+                "ct_select",
+                Box::new(|x| u4096::ct_select(&secret, &x, x.ct_eq(&secret))),
+            ),
+            (
+                "ct_swap",
+                Box::new(|x| {
+                    let (mut a, mut b) = (secret, x);
+                    u4096::ct_swap(&mut a, &mut b, x.ct_eq(&secret));
+                    a
+                }),
+            ),
+        ];
+
+        for (name, op) in ops {
+            let t = leakage(20_000, op, secret, &random);
+
+            assert!(t.abs() < threshold, "{} leaks timing, t = {}", name, t);
+        }
+    }
+
+    // Secret exponents against a public modulus, wide enough for Karatsuba to
+    // apply to plain products; each call is a full ladder, so fewer rounds are
+    // taken.
+    #[test]
+    #[ignore]
+    fn constant_time_pow_does_not_leak() {
+        let random = random_inputs::<u2048>();
+        let (base, secret) = (random[0], random[1]);
+        let modulus = random[2] | u2048::ONE;
+        let threshold = 10.0;
+
+        let ctx = Montgomery::new(modulus).unwrap();
+        let base_mont = ctx.to_montgomery(base);
+        let barrett = Barrett::new(modulus).unwrap();
+        let base_reduced = base % modulus;
+        // 2^2048 - 1942289, reduced in the word sized c form
+        let solinas = Solinas::new(u2048::MAX - u2048::from(1_942_288)).unwrap();
+
+        let ops: [(&str, Op<u2048>); 4] = [
+            ("pow_mod_ct", Box::new(|x| base.pow_mod_ct(x, modulus))),
+            ("Montgomery::pow_ct", Box::new(|x| ctx.pow_ct(base_mont, x))),
+            (
+                "Barrett::pow_ct",
+                Box::new(|x| barrett.pow_ct(base_reduced, x)),
+            ),
+            ("Solinas::pow_ct", Box::new(|x| solinas.pow_ct(base, x))),
+        ];
+
+        for (name, op) in ops {
+            let t = leakage(200, op, secret, &random);
+
+            assert!(t.abs() < threshold, "{} leaks timing, t = {}", name, t);
+        }
+    }
+
+    // Sanity check that the harness catches a variable time operation: long
+    // division returns early when the dividend is smaller than the divisor.
+    #[test]
+    #[ignore]
+    fn harness_detects_variable_time_div() {
+        let random = random_inputs::<u4096>();
+        let divisor = random[1] >> 2048;
+
+        let t = leakage(20_000, |x| x % divisor, u4096::ONE, &random);
+
+        assert!(t.abs() > 10.0, "expected division to leak, t = {}", t);
+    }
+}
@@ -0,0 +1,36 @@
+use std::fmt;
+
+/// The error returned when a checked integer conversion fails.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct TryFromIntError;
+
+impl fmt::Display for TryFromIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("out of range integral type conversion attempted")
+    }
+}
+
+impl std::error::Error for TryFromIntError {}
+
+/// The error returned when parsing a generated integer type from a string fails.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum ParseIntError {
+    /// The input was empty.
+    Empty,
+    /// The input contained a character that is not a digit of the radix.
+    InvalidDigit,
+    /// The value does not fit in the target type.
+    Overflow,
+}
+
+impl fmt::Display for ParseIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ParseIntError::Empty => "cannot parse integer from empty string",
+            ParseIntError::InvalidDigit => "invalid digit found in string",
+            ParseIntError::Overflow => "number too large to fit in target type",
+        })
+    }
+}
+
+impl std::error::Error for ParseIntError {}
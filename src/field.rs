@@ -0,0 +1,281 @@
+//! Compile time helpers for the `#[prime_field]` generated types.
+//!
+//! The attribute checks the modulus with Baillie-PSW as it expands, so a
+//! composite modulus, whose inverses and square roots would be wrong, does not
+//! compile:
+//!
+//! ```
+//! # use librypt_int::{prime_field, u24};
+//! #[prime_field(modulus = "0x3c7", repr = u24)]
+//! #[derive(Clone, Copy, PartialEq, Eq)]
+//! struct Fp967;
+//! ```
+//!
+//! ```compile_fail
+//! # use librypt_int::{prime_field, u24};
+//! // 31^2
+//! #[prime_field(modulus = "0x3c1", repr = u24)]
+//! #[derive(Clone, Copy, PartialEq, Eq)]
+//! struct Fp961;
+//! ```
+
+/// Zero extends the little-endian words of a modulus to `N` words.
+pub const fn words<const N: usize>(src: &[u64]) -> [u64; N] {
+    assert!(src.len() <= N, "modulus does not fit the repr type");
+
+    let mut ret = [0u64; N];
+    let mut i = 0;
+
+    while i < src.len() {
+        ret[i] = src[i];
+        i += 1;
+    }
+
+    ret
+}
+
+/// Returns `(-modulus^-1 mod 2^64, R mod modulus, R^2 mod modulus)` for an odd
+/// `modulus` above one, with `R = 2^(64 * N)`, as taken by
+/// [`Montgomery::from_raw_parts`](crate::Montgomery::from_raw_parts).
+pub const fn montgomery_params<const N: usize>(modulus: [u64; N]) -> (u64, [u64; N], [u64; N]) {
+    assert!(
+        modulus[0] & 1 == 1,
+        "Montgomery form requires an odd modulus"
+    );
+
+    let mut inv = 1u64;
+    let mut i = 0;
+
+    while i < 6 {
+        inv = inv.wrapping_mul(2u64.wrapping_sub(modulus[0].wrapping_mul(inv)));
+        i += 1;
+    }
+
+    let mut r = [0u64; N];
+    r[0] = 1;
+
+    let mut i = 0;
+    while i < 64 * N {
+        r = double_mod(r, &modulus);
+        i += 1;
+    }
+
+    let mut r2 = r;
+    let mut i = 0;
+    while i < 64 * N {
+        r2 = double_mod(r2, &modulus);
+        i += 1;
+    }
+
+    (inv.wrapping_neg(), r, r2)
+}
+
+/// `2 * words mod modulus` for `words < modulus`.
+pub(crate) const fn double_mod<const N: usize>(
+    mut words: [u64; N],
+    modulus: &[u64; N],
+) -> [u64; N] {
+    let top = words[N - 1] >> 63;
+    let mut i = N - 1;
+
+    while i > 0 {
+        words[i] = words[i] << 1 | words[i - 1] >> 63;
+        i -= 1;
+    }
+
+    words[0] <<= 1;
+
+    if top == 0 && lt(&words, modulus) {
+        return words;
+    }
+
+    let mut borrow = false;
+    let mut i = 0;
+
+    while i < N {
+        let (diff, b1) = words[i].overflowing_sub(modulus[i]);
+        let (diff, b2) = diff.overflowing_sub(borrow as u64);
+        words[i] = diff;
+        borrow = b1 || b2;
+        i += 1;
+    }
+
+    words
+}
+
+const fn lt<const N: usize>(lhs: &[u64; N], rhs: &[u64; N]) -> bool {
+    let mut i = N;
+
+    while i > 0 {
+        i -= 1;
+
+        if lhs[i] != rhs[i] {
+            return lhs[i] < rhs[i];
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{prime_field, u256, u512};
+
+    /// 2^255 - 19
+    #[prime_field(
+        modulus = "0x7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed",
+        repr = u256
+    )]
+    #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+    struct Fp25519;
+
+    /// secp256k1 base field, 2^256 - 2^32 - 977
+    #[prime_field(
+        modulus = "0xffffffff_ffffffff_ffffffff_ffffffff_ffffffff_ffffffff_fffffffe_fffffc2f",
+        repr = u256
+    )]
+    #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+    struct FpK256;
+
+    /// P-256 base field, 2^256 - 2^224 + 2^192 + 2^96 - 1
+    #[prime_field(
+        modulus = "115792089210356248762697446949407573530086143415290314195533631308867097853951",
+        repr = u256
+    )]
+    #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+    struct FpP256;
+
+    #[prime_field(
+        modulus = "0x7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed",
+        repr = u256,
+        reduction = "solinas"
+    )]
+    #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+    struct Fp25519Solinas;
+
+    #[prime_field(
+        modulus = "115792089210356248762697446949407573530086143415290314195533631308867097853951",
+        repr = u256,
+        reduction = "solinas"
+    )]
+    #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+    struct FpP256Solinas;
+
+    /// P-384 base field, 2^384 - 2^128 - 2^96 + 2^32 - 1
+    #[prime_field(
+        modulus = "0xffffffff_ffffffff_ffffffff_ffffffff_ffffffff_ffffffff_ffffffff_fffffffe_ffffffff_00000000_00000000_ffffffff",
+        repr = u512
+    )]
+    #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+    struct FpP384;
+
+    #[prime_field(
+        modulus = "0xffffffff_ffffffff_ffffffff_ffffffff_ffffffff_ffffffff_ffffffff_fffffffe_ffffffff_00000000_00000000_ffffffff",
+        repr = u512,
+        reduction = "solinas"
+    )]
+    #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+    struct FpP384Solinas;
+
+    #[test]
+    fn constants() {
+        assert_eq!(Fp25519::MODULUS, (u256::ONE << 255) - u256::from(19));
+        assert_eq!(
+            FpP256::MODULUS,
+            u256::from_str_radix(
+                "ffffffff00000001000000000000000000000000ffffffffffffffffffffffff",
+                16
+            )
+            .unwrap()
+        );
+        assert_eq!(Fp25519::ONE.to_repr(), u256::ONE);
+        assert_eq!(FpK256::ZERO.to_repr(), u256::ZERO);
+        assert_eq!(Fp25519::new(Fp25519::MODULUS), Fp25519::ZERO);
+        assert_eq!(Fp25519::from_repr(Fp25519::MODULUS), None);
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = FpK256::new(u256::MAX);
+        let b = FpK256::new(u256::from(u128::MAX) << 64);
+
+        assert_eq!((a + b) - b, a);
+        assert_eq!(a - a, FpK256::ZERO);
+        assert_eq!(-a + a, FpK256::ZERO);
+        assert_eq!(a * a.invert().unwrap(), FpK256::ONE);
+        assert_eq!(FpK256::ZERO.invert(), None);
+        assert_eq!(
+            (a * b).to_repr(),
+            a.to_repr().mul_mod(b.to_repr(), FpK256::MODULUS)
+        );
+
+        let mut c = a;
+        c *= &b;
+        c += FpK256::ONE;
+        c -= &FpK256::ONE;
+        assert_eq!(c, a * b);
+    }
+
+    #[test]
+    fn square_roots() {
+        // p = 5 mod 8 and p = 3 mod 4 (and P-256's 2-adicity of 1) exercise
+        // different Tonelli-Shanks paths
+        for x in [2u32, 3, 5, 1 << 20, 123_456_789] {
+            let x = u256::from(x);
+
+            let a = Fp25519::new(x).square();
+            assert_eq!(a.sqrt().unwrap().square(), a);
+            assert_eq!(a.legendre(), 1);
+
+            let a = FpK256::new(x).square();
+            assert_eq!(a.sqrt().unwrap().square(), a);
+
+            let a = FpP256::new(x).square();
+            assert_eq!(a.sqrt().unwrap().square(), a);
+        }
+
+        // 2 is not a square modulo 2^255 - 19
+        assert!(!Fp25519::new(u256::from(2)).is_square());
+        assert_eq!(Fp25519::new(u256::from(2)).sqrt(), None);
+        assert_eq!(Fp25519::ZERO.legendre(), 0);
+        assert_eq!(Fp25519::ZERO.sqrt(), Some(Fp25519::ZERO));
+    }
+
+    #[test]
+    fn reductions_agree() {
+        let x = u256::from(u128::MAX) << 90;
+        let y = u256::MAX - u256::from(77);
+
+        let (a, b) = (Fp25519::new(x), Fp25519::new(y));
+        let (c, d) = (Fp25519Solinas::new(x), Fp25519Solinas::new(y));
+        assert_eq!((a * b + a).to_repr(), (c * d + c).to_repr());
+        assert_eq!((a - b).to_repr(), (c - d).to_repr());
+        assert_eq!(a.invert().unwrap().to_repr(), c.invert().unwrap().to_repr());
+        assert_eq!(c.square().sqrt().unwrap().square(), c.square());
+        assert_eq!(Fp25519Solinas::ONE.to_repr(), u256::ONE);
+
+        let (a, b) = (FpP256::new(x), FpP256::new(y));
+        let (c, d) = (FpP256Solinas::new(x), FpP256Solinas::new(y));
+        assert_eq!((a * b - a).to_repr(), (c * d - c).to_repr());
+        assert_eq!(a.pow(y).to_repr(), c.pow(y).to_repr());
+
+        let x = u512::from(u128::MAX) << 300;
+        let y = u512::MAX - u512::from(77);
+        let (a, b) = (FpP384::new(x), FpP384::new(y));
+        let (c, d) = (FpP384Solinas::new(x), FpP384Solinas::new(y));
+        assert_eq!((a * b + a).to_repr(), (c * d + c).to_repr());
+        assert_eq!(a.invert().unwrap().to_repr(), c.invert().unwrap().to_repr());
+        assert_eq!(c.square().sqrt().unwrap().square(), c.square());
+    }
+
+    #[test]
+    fn encoding() {
+        let a = FpP256::new(u256::from(0x1234_5678u32));
+
+        assert_eq!(FpP256::from_be_bytes(a.to_be_bytes()), Some(a));
+        assert_eq!(FpP256::from_le_bytes(a.to_le_bytes()), Some(a));
+        assert_eq!(FpP256::from_be_bytes([0xff; 32]), None);
+        assert_eq!(format!("{}", a), "305419896");
+        assert_eq!(format!("{:?}", a), "FpP256(0x12345678)");
+    }
+}
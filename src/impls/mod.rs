@@ -0,0 +1,24 @@
+//! Impls for optional dependencies, applied to the generated types from `src/lib.rs`.
+
+#[cfg(feature = "subtle")]
+#[macro_use]
+mod subtle;
+
+#[cfg(feature = "rand")]
+#[macro_use]
+mod rand;
+
+#[cfg(feature = "rand")]
+pub use self::rand::UniformBitUint;
+
+#[cfg(feature = "zeroize")]
+#[macro_use]
+mod zeroize;
+
+#[cfg(feature = "serde")]
+#[macro_use]
+mod serde;
+
+#[cfg(feature = "num-traits")]
+#[macro_use]
+mod num_traits;
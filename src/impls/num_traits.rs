@@ -0,0 +1,340 @@
+macro_rules! impl_num_traits {
+    ($t:ty) => {
+        impl ::num_traits::Zero for $t {
+            #[inline]
+            fn zero() -> $t {
+                <$t>::ZERO
+            }
+
+            #[inline]
+            fn is_zero(&self) -> bool {
+                *self == <$t>::ZERO
+            }
+        }
+
+        impl ::num_traits::One for $t {
+            #[inline]
+            fn one() -> $t {
+                <$t>::ONE
+            }
+        }
+
+        impl ::num_traits::Num for $t {
+            type FromStrRadixErr = $crate::ParseIntError;
+
+            #[inline]
+            fn from_str_radix(src: &str, radix: u32) -> Result<$t, $crate::ParseIntError> {
+                <$t>::from_str_radix(src, radix)
+            }
+        }
+
+        impl ::num_traits::Unsigned for $t {}
+
+        impl ::num_traits::Bounded for $t {
+            #[inline]
+            fn min_value() -> $t {
+                <$t>::MIN
+            }
+
+            #[inline]
+            fn max_value() -> $t {
+                <$t>::MAX
+            }
+        }
+
+        impl ::num_traits::ToPrimitive for $t {
+            #[inline]
+            fn to_i64(&self) -> Option<i64> {
+                self.to_u128().and_then(|value| i64::try_from(value).ok())
+            }
+
+            #[inline]
+            fn to_i128(&self) -> Option<i128> {
+                self.to_u128().and_then(|value| i128::try_from(value).ok())
+            }
+
+            #[inline]
+            fn to_u64(&self) -> Option<u64> {
+                self.to_u128().and_then(|value| u64::try_from(value).ok())
+            }
+
+            #[inline]
+            fn to_u128(&self) -> Option<u128> {
+                (self.bits() <= 128).then(|| u128::from(*self))
+            }
+        }
+
+        impl ::num_traits::FromPrimitive for $t {
+            #[inline]
+            fn from_i64(n: i64) -> Option<$t> {
+                u128::try_from(n).ok().and_then(Self::from_u128)
+            }
+
+            #[inline]
+            fn from_i128(n: i128) -> Option<$t> {
+                u128::try_from(n).ok().and_then(Self::from_u128)
+            }
+
+            #[inline]
+            fn from_u64(n: u64) -> Option<$t> {
+                Self::from_u128(n.into())
+            }
+
+            /// `None` if `n` does not fit, where `From<u128>` truncates.
+            #[inline]
+            fn from_u128(n: u128) -> Option<$t> {
+                let value = <$t>::from(n);
+
+                (u128::from(value) == n).then_some(value)
+            }
+        }
+
+        impl ::num_traits::NumCast for $t {
+            #[inline]
+            fn from<N: ::num_traits::ToPrimitive>(n: N) -> Option<$t> {
+                n.to_u128()
+                    .and_then(<$t as ::num_traits::FromPrimitive>::from_u128)
+            }
+        }
+
+        impl ::num_traits::CheckedAdd for $t {
+            #[inline]
+            fn checked_add(&self, v: &$t) -> Option<$t> {
+                <$t>::checked_add(*self, *v)
+            }
+        }
+
+        impl ::num_traits::CheckedSub for $t {
+            #[inline]
+            fn checked_sub(&self, v: &$t) -> Option<$t> {
+                <$t>::checked_sub(*self, *v)
+            }
+        }
+
+        impl ::num_traits::CheckedMul for $t {
+            #[inline]
+            fn checked_mul(&self, v: &$t) -> Option<$t> {
+                <$t>::checked_mul(*self, *v)
+            }
+        }
+
+        impl ::num_traits::CheckedDiv for $t {
+            #[inline]
+            fn checked_div(&self, v: &$t) -> Option<$t> {
+                <$t>::checked_div(*self, *v)
+            }
+        }
+
+        impl ::num_traits::WrappingAdd for $t {
+            #[inline]
+            fn wrapping_add(&self, v: &$t) -> $t {
+                <$t>::wrapping_add(*self, *v)
+            }
+        }
+
+        impl ::num_traits::WrappingSub for $t {
+            #[inline]
+            fn wrapping_sub(&self, v: &$t) -> $t {
+                <$t>::wrapping_sub(*self, *v)
+            }
+        }
+
+        impl ::num_traits::WrappingMul for $t {
+            #[inline]
+            fn wrapping_mul(&self, v: &$t) -> $t {
+                <$t>::wrapping_mul(*self, *v)
+            }
+        }
+
+        impl ::num_traits::Saturating for $t {
+            #[inline]
+            fn saturating_add(self, v: $t) -> $t {
+                <$t>::saturating_add(self, v)
+            }
+
+            #[inline]
+            fn saturating_sub(self, v: $t) -> $t {
+                <$t>::saturating_sub(self, v)
+            }
+        }
+
+        impl ::num_traits::Pow<u32> for $t {
+            type Output = $t;
+
+            #[inline]
+            fn pow(self, rhs: u32) -> $t {
+                <$t>::pow(self, rhs)
+            }
+        }
+
+        impl ::num_traits::PrimInt for $t {
+            #[inline]
+            fn count_ones(self) -> u32 {
+                <$t>::count_ones(self)
+            }
+
+            #[inline]
+            fn count_zeros(self) -> u32 {
+                <$t>::count_zeros(self)
+            }
+
+            #[inline]
+            fn leading_zeros(self) -> u32 {
+                <$t>::leading_zeros(self)
+            }
+
+            #[inline]
+            fn trailing_zeros(self) -> u32 {
+                <$t>::trailing_zeros(self)
+            }
+
+            fn rotate_left(self, n: u32) -> $t {
+                let n = n % <$t>::BITS;
+
+                if n == 0 {
+                    self
+                } else {
+                    (self << n) | (self >> (<$t>::BITS - n))
+                }
+            }
+
+            fn rotate_right(self, n: u32) -> $t {
+                self.rotate_left(<$t>::BITS - n % <$t>::BITS)
+            }
+
+            /// Shifts right filling with the top bit, as the signed counterpart would.
+            fn signed_shr(self, n: u32) -> $t {
+                if self.bit(<$t>::BITS - 1) && n != 0 {
+                    (self >> n) | !(<$t>::MAX >> n)
+                } else {
+                    self >> n
+                }
+            }
+
+            #[inline]
+            fn signed_shl(self, n: u32) -> $t {
+                self << n
+            }
+
+            #[inline]
+            fn unsigned_shl(self, n: u32) -> $t {
+                self << n
+            }
+
+            #[inline]
+            fn unsigned_shr(self, n: u32) -> $t {
+                self >> n
+            }
+
+            #[inline]
+            fn swap_bytes(self) -> $t {
+                <$t>::from_le_bytes(self.to_be_bytes())
+            }
+
+            // the limbs have no byte order of their own, so these follow the
+            // primitive types through the native byte representation
+
+            #[inline]
+            fn from_be(x: $t) -> $t {
+                <$t>::from_ne_bytes(x.to_be_bytes())
+            }
+
+            #[inline]
+            fn from_le(x: $t) -> $t {
+                <$t>::from_ne_bytes(x.to_le_bytes())
+            }
+
+            #[inline]
+            fn to_be(self) -> $t {
+                <$t>::from_ne_bytes(self.to_be_bytes())
+            }
+
+            #[inline]
+            fn to_le(self) -> $t {
+                <$t>::from_ne_bytes(self.to_le_bytes())
+            }
+
+            #[inline]
+            fn pow(self, exp: u32) -> $t {
+                <$t>::pow(self, exp)
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use num_traits::{
+        Bounded, CheckedDiv, FromPrimitive, Num, One, PrimInt, Saturating, ToPrimitive,
+        WrappingSub, Zero,
+    };
+
+    use crate::{u24, u256};
+
+    /// Written against `num-traits` only, as downstream generic code is.
+    fn gcd<T: PrimInt>(mut a: T, mut b: T) -> T {
+        while !b.is_zero() {
+            (a, b) = (b, a % b);
+        }
+
+        a
+    }
+
+    fn sum_of_powers<T: PrimInt + num_traits::Unsigned>(n: u32) -> T {
+        (0..n).fold(T::zero(), |acc, i| acc + T::from(2).unwrap().pow(i))
+    }
+
+    #[test]
+    fn num_traits() {
+        assert_eq!(
+            gcd(u256::from(2u64 * 3 * 7 * 11), u256::from(3u64 * 11 * 13)),
+            u256::from(33)
+        );
+        assert_eq!(sum_of_powers::<u256>(200), (u256::ONE << 200) - u256::ONE);
+        assert_eq!(sum_of_powers::<u24>(24), u24::MAX);
+
+        assert!(u256::zero().is_zero() && u256::one() == u256::ONE);
+        assert_eq!(<u24 as Bounded>::max_value(), u24::MAX);
+        assert_eq!(
+            <u256 as Num>::from_str_radix("ff", 16).unwrap(),
+            u256::from(255)
+        );
+        assert_eq!(CheckedDiv::checked_div(&u256::ONE, &u256::ZERO), None);
+        assert_eq!(WrappingSub::wrapping_sub(&u24::ZERO, &u24::ONE), u24::MAX);
+        assert_eq!(Saturating::saturating_add(u24::MAX, u24::ONE), u24::MAX);
+
+        // conversions fail rather than truncate
+        assert_eq!(u256::from(300).to_u8(), None);
+        assert_eq!(u256::from(300).to_i16(), Some(300));
+        assert_eq!(u256::from(u128::MAX).to_u128(), Some(u128::MAX));
+        assert_eq!((u256::ONE << 128).to_u128(), None);
+        assert_eq!(u24::MAX.to_i64(), Some(0xff_ffff));
+        assert_eq!(u24::from_u32(1 << 24), None);
+        assert_eq!(u24::from_i32(-1), None);
+        assert_eq!(u256::from_u128(u128::MAX), Some(u256::from(u128::MAX)));
+        assert_eq!(
+            <u24 as num_traits::NumCast>::from(7.9f64),
+            Some(u24::from(7))
+        );
+
+        let x = u256::from_str_radix("0123456789abcdef", 16).unwrap() << 192;
+        assert_eq!(x.rotate_left(64), u256::from(0x0123_4567_89ab_cdefu64));
+        assert_eq!(x.rotate_right(192), u256::from(0x0123_4567_89ab_cdefu64));
+        assert_eq!(x.rotate_left(256), x);
+        assert_eq!(u24::from(0x80_0001).rotate_left(1), u24::from(3));
+        assert_eq!(
+            PrimInt::swap_bytes(u24::from(0x12_3456)),
+            u24::from(0x56_3412)
+        );
+        assert_eq!(u256::from_be(x.to_be()), x);
+        assert_eq!(u256::from_le(x.to_le()), x);
+        assert_eq!(u24::from(0x80_0000).signed_shr(4), u24::from(0xf8_0000));
+        assert_eq!(u24::from(0x40_0000).signed_shr(4), u24::from(0x04_0000));
+        assert_eq!(u256::MAX.signed_shr(255), u256::MAX);
+        assert_eq!(PrimInt::pow(u256::from(3), 5), u256::from(243));
+
+        // shifts take any primitive integer, as on the primitive types
+        assert_eq!(u256::ONE << 3usize, u256::from(8));
+        assert_eq!(u256::from(8) >> 3i64, u256::ONE);
+    }
+}
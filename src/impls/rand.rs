@@ -0,0 +1,188 @@
+/// Sampler behind `gen_range` for the generated types, drawing uniformly from
+/// `low..low + range` by rejection sampling.
+#[derive(Clone, Copy, Debug)]
+pub struct UniformBitUint<T> {
+    pub(crate) low: T,
+    /// size of the range, zero for the full range of the type
+    pub(crate) range: T,
+    /// drops the random bits above those of `range - 1`
+    pub(crate) shift: u32,
+}
+
+macro_rules! impl_rand {
+    ($t:ty) => {
+        impl ::rand::distributions::Distribution<$t> for ::rand::distributions::Standard {
+            #[inline]
+            fn sample<R: ::rand::Rng + ?Sized>(&self, rng: &mut R) -> $t {
+                let mut bytes = [0u8; <$t>::BYTES];
+                rng.fill_bytes(&mut bytes);
+
+                <$t>::from_le_bytes(bytes)
+            }
+        }
+
+        impl ::rand::distributions::uniform::SampleUniform for $t {
+            type Sampler = $crate::UniformBitUint<$t>;
+        }
+
+        impl ::rand::distributions::uniform::UniformSampler for $crate::UniformBitUint<$t> {
+            type X = $t;
+
+            fn new<B1, B2>(low: B1, high: B2) -> Self
+            where
+                B1: ::rand::distributions::uniform::SampleBorrow<$t> + Sized,
+                B2: ::rand::distributions::uniform::SampleBorrow<$t> + Sized,
+            {
+                let (low, high) = (*low.borrow(), *high.borrow());
+                assert!(low < high, "Uniform::new called with `low >= high`");
+
+                Self::new_inclusive(low, high - <$t>::ONE)
+            }
+
+            fn new_inclusive<B1, B2>(low: B1, high: B2) -> Self
+            where
+                B1: ::rand::distributions::uniform::SampleBorrow<$t> + Sized,
+                B2: ::rand::distributions::uniform::SampleBorrow<$t> + Sized,
+            {
+                let (low, high) = (*low.borrow(), *high.borrow());
+                assert!(
+                    low <= high,
+                    "Uniform::new_inclusive called with `low > high`"
+                );
+
+                // wraps to zero for the full range
+                let range = (high - low).overflowing_add(<$t>::ONE).0;
+                let shift = if range == <$t>::ZERO {
+                    0
+                } else {
+                    (range - <$t>::ONE).leading_zeros().min(<$t>::BITS - 1)
+                };
+
+                $crate::UniformBitUint { low, range, shift }
+            }
+
+            fn sample<R: ::rand::Rng + ?Sized>(&self, rng: &mut R) -> $t {
+                // each draw is accepted with probability over one half
+                loop {
+                    let value = rng.gen::<$t>() >> self.shift;
+
+                    if self.range == <$t>::ZERO {
+                        return value;
+                    }
+
+                    if value < self.range {
+                        return self.low + value;
+                    }
+                }
+            }
+        }
+    };
+}
+
+macro_rules! impl_random_prime {
+    ($($t:ty),*) => {
+        $(
+            impl $t {
+                /// Draws a random `bits` bit prime with the top two bits set, so the
+                /// product of two has exactly `2 * bits` bits as RSA moduli need.
+                ///
+                /// Panics unless `16 <= bits <= Self::BITS`.
+                pub fn random_prime<R>(bits: u32, rng: &mut R) -> $t
+                where
+                    R: ::rand_core::CryptoRng + ::rand_core::RngCore + ?Sized,
+                {
+                    $crate::prime::random_prime(bits, false, rng)
+                }
+
+                /// Draws a random `bits` bit safe prime `p = 2q + 1`, with `q` prime
+                /// and the top two bits of `p` set, for Diffie-Hellman groups.
+                ///
+                /// Panics unless `16 <= bits <= Self::BITS`.
+                pub fn random_safe_prime<R>(bits: u32, rng: &mut R) -> $t
+                where
+                    R: ::rand_core::CryptoRng + ::rand_core::RngCore + ?Sized,
+                {
+                    $crate::prime::random_prime(bits, true, rng)
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{u1024, u512};
+    use rand_chacha::rand_core::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn random_primes() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0x5eed);
+
+        for bits in [16, 100, 512] {
+            let p = u512::random_prime(bits, &mut rng);
+
+            assert_eq!(p.bits(), bits);
+            assert!(p.bit(bits - 2));
+            assert!(crate::prime::is_probable_prime_with_rng(p, 20, &mut rng));
+        }
+
+        let p = u1024::random_prime(1024, &mut rng);
+        assert_eq!(p.bits(), 1024);
+        assert!(crate::prime::is_probable_prime_with_rng(p, 20, &mut rng));
+
+        let p = u512::random_safe_prime(128, &mut rng);
+        let q = p >> 1;
+        assert_eq!(p.bits(), 128);
+        assert!(p.bit(126));
+        assert!(p.is_prime_bpsw() && q.is_prime_bpsw());
+
+        assert_ne!(
+            u512::random_prime(256, &mut rng),
+            u512::random_prime(256, &mut rng)
+        );
+    }
+
+    #[test]
+    fn sampling() {
+        use crate::u256;
+        use rand::Rng;
+
+        let mut rng = ChaCha8Rng::seed_from_u64(0x5eed);
+
+        let a: u256 = rng.gen();
+        let b: u256 = rng.gen();
+        assert_ne!(a, b);
+        assert!(a.bits() > 200 && b.bits() > 200);
+
+        // every value of a short range, in roughly equal counts
+        let mut counts = [0u32; 10];
+        for _ in 0..10_000 {
+            let value = rng.gen_range(u256::from(5)..u256::from(15));
+            counts[u8::from(value - u256::from(5)) as usize] += 1;
+        }
+        assert!(
+            counts.iter().all(|&count| (900..1100).contains(&count)),
+            "{:?}",
+            counts
+        );
+
+        // just above a power of two, where masking alone would be biased
+        let modulus = (u256::ONE << 200) + u256::ONE;
+        for _ in 0..1000 {
+            let value = rng.gen_range(u256::ONE..modulus);
+            assert!(value >= u256::ONE && value < modulus);
+        }
+
+        assert_eq!(rng.gen_range(u256::MAX..=u256::MAX), u256::MAX);
+        assert_eq!(rng.gen_range(u256::ZERO..u256::ONE), u256::ZERO);
+        let full = rng.gen_range(u256::ZERO..=u256::MAX);
+        assert_ne!(full, rng.gen_range(u256::ZERO..=u256::MAX));
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn random_prime_too_wide() {
+        u512::random_prime(513, &mut ChaCha8Rng::seed_from_u64(0));
+    }
+}
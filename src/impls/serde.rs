@@ -0,0 +1,107 @@
+macro_rules! impl_serde {
+    ($t:ty) => {
+        /// Serializes as a `0x` prefixed hex string in human readable formats and
+        /// as a tuple of `BYTES` big-endian bytes, without a length, otherwise.
+        impl ::serde::Serialize for $t {
+            fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                use ::serde::ser::SerializeTuple;
+
+                if serializer.is_human_readable() {
+                    return serializer.collect_str(&format_args!("{:#x}", self));
+                }
+
+                let mut tuple = serializer.serialize_tuple(<$t>::BYTES)?;
+                for byte in self.to_be_bytes() {
+                    tuple.serialize_element(&byte)?;
+                }
+
+                tuple.end()
+            }
+        }
+
+        impl<'de> ::serde::Deserialize<'de> for $t {
+            fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<$t, D::Error> {
+                use ::serde::de::{Error, SeqAccess, Unexpected};
+
+                struct Visitor;
+
+                impl<'de> ::serde::de::Visitor<'de> for Visitor {
+                    type Value = $t;
+
+                    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(f, "a 0x prefixed hex string or {} bytes", <$t>::BYTES)
+                    }
+
+                    fn visit_str<E: Error>(self, value: &str) -> Result<$t, E> {
+                        if !value.starts_with("0x") {
+                            return Err(E::invalid_value(Unexpected::Str(value), &self));
+                        }
+
+                        value.parse().map_err(E::custom)
+                    }
+
+                    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<$t, A::Error> {
+                        let mut bytes = [0u8; <$t>::BYTES];
+
+                        for (i, byte) in bytes.iter_mut().enumerate() {
+                            *byte = seq
+                                .next_element()?
+                                .ok_or_else(|| A::Error::invalid_length(i, &self))?;
+                        }
+
+                        Ok(<$t>::from_be_bytes(bytes))
+                    }
+                }
+
+                if deserializer.is_human_readable() {
+                    deserializer.deserialize_str(Visitor)
+                } else {
+                    deserializer.deserialize_tuple(<$t>::BYTES, Visitor)
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{u24, u256, u4096};
+
+    #[test]
+    fn serde() {
+        let value = u256::from(0xdead_beef_u64) << 128;
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(json, "\"0xdeadbeef00000000000000000000000000000000\"");
+        assert_eq!(serde_json::from_str::<u256>(&json).unwrap(), value);
+        assert_eq!(serde_json::to_string(&u24::ZERO).unwrap(), "\"0x0\"");
+        assert_eq!(
+            serde_json::from_str::<u24>("\"0xABCDEF\"").unwrap(),
+            u24::from(0xab_cdef)
+        );
+
+        for bad in [
+            "\"ff\"",
+            "\"0x\"",
+            "\"0x+1\"",
+            "\"0x1000000\"",
+            "\"0xfg\"",
+            "255",
+        ] {
+            assert!(serde_json::from_str::<u24>(bad).is_err(), "{}", bad);
+        }
+
+        // fixed width big-endian, with no length prefix
+        let bytes = bincode::serialize(&value).unwrap();
+        assert_eq!(bytes, value.to_be_bytes());
+        assert_eq!(bincode::deserialize::<u256>(&bytes).unwrap(), value);
+        assert!(bincode::deserialize::<u256>(&bytes[1..]).is_err());
+
+        let max = bincode::serialize(&u4096::MAX).unwrap();
+        assert_eq!(max, [0xff; 512]);
+        assert_eq!(bincode::deserialize::<u4096>(&max).unwrap(), u4096::MAX);
+
+        let pair = (u24::from(1), u256::MAX);
+        let json = serde_json::to_string(&pair).unwrap();
+        assert_eq!(serde_json::from_str::<(u24, u256)>(&json).unwrap(), pair);
+    }
+}
@@ -0,0 +1,87 @@
+impl From<crate::Choice> for ::subtle::Choice {
+    #[inline]
+    fn from(choice: crate::Choice) -> ::subtle::Choice {
+        ::subtle::Choice::from(choice.unwrap_u8())
+    }
+}
+
+impl From<::subtle::Choice> for crate::Choice {
+    #[inline]
+    fn from(choice: ::subtle::Choice) -> crate::Choice {
+        crate::Choice::from(choice.unwrap_u8())
+    }
+}
+
+macro_rules! impl_subtle {
+    ($t:ty) => {
+        impl ::subtle::ConstantTimeEq for $t {
+            #[inline]
+            fn ct_eq(&self, other: &$t) -> ::subtle::Choice {
+                <$t>::ct_eq(self, other).into()
+            }
+        }
+
+        impl ::subtle::ConstantTimeGreater for $t {
+            #[inline]
+            fn ct_gt(&self, other: &$t) -> ::subtle::Choice {
+                <$t>::ct_gt(self, other).into()
+            }
+        }
+
+        impl ::subtle::ConstantTimeLess for $t {
+            #[inline]
+            fn ct_lt(&self, other: &$t) -> ::subtle::Choice {
+                <$t>::ct_lt(self, other).into()
+            }
+        }
+
+        impl ::subtle::ConditionallySelectable for $t {
+            #[inline]
+            fn conditional_select(a: &$t, b: &$t, choice: ::subtle::Choice) -> $t {
+                <$t>::ct_select(a, b, choice.into())
+            }
+
+            #[inline]
+            fn conditional_swap(a: &mut $t, b: &mut $t, choice: ::subtle::Choice) {
+                <$t>::ct_swap(a, b, choice.into())
+            }
+        }
+
+        /// Two's complement negation, which together with `ConditionallySelectable`
+        /// provides `subtle::ConditionallyNegatable`.
+        impl std::ops::Neg for &$t {
+            type Output = $t;
+
+            #[inline]
+            fn neg(self) -> $t {
+                <$t>::ZERO.ct_sub(*self).0
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{u256, u80};
+    use subtle::{
+        Choice, ConditionallyNegatable, ConditionallySelectable, ConstantTimeEq,
+        ConstantTimeGreater, ConstantTimeLess,
+    };
+
+    #[test]
+    fn traits() {
+        let a = u256::from(u128::MAX) << 64;
+        let b = u256::from(7);
+
+        assert!(bool::from(ConstantTimeEq::ct_eq(&a, &a)));
+        assert!(bool::from(ConstantTimeGreater::ct_gt(&a, &b)));
+        assert!(bool::from(ConstantTimeLess::ct_lt(&b, &a)));
+        assert_eq!(u256::conditional_select(&a, &b, Choice::from(1)), b);
+
+        let mut c = u80::from(5);
+        c.conditional_negate(Choice::from(0));
+        assert_eq!(c, u80::from(5));
+        c.conditional_negate(Choice::from(1));
+        assert_eq!(c, u80::MAX - u80::from(4));
+    }
+}
@@ -0,0 +1,7 @@
+macro_rules! impl_zeroize {
+    ($t:ty) => {
+        // the all zero value is `Default`, so zeroize overwrites with it using
+        // volatile writes
+        impl ::zeroize::DefaultIsZeroes for $t {}
+    };
+}
@@ -1,41 +1,604 @@
-pub use bitint_macro::bituint;
+extern crate self as librypt_int;
+
+pub use barrett::Barrett;
+pub use bitint_macro::{bitint, bituint, prime_field};
+pub use ct::Choice;
+pub use error::{ParseIntError, TryFromIntError};
+#[cfg(feature = "rand")]
+pub use impls::UniformBitUint;
+pub use montgomery::Montgomery;
 use paste::paste;
+#[cfg(feature = "zeroize")]
+pub use secret::Secret;
+pub use solinas::Solinas;
+pub use uint::{BitUint, ShiftAmount};
 
-/// Fixed bit integers
+mod barrett;
+pub mod ct;
+mod error;
+#[doc(hidden)]
+pub mod field;
+#[macro_use]
+mod impls;
+#[doc(hidden)]
+pub mod limbs;
+mod montgomery;
+#[doc(hidden)]
+pub mod pow;
+pub mod prime;
+#[doc(hidden)]
+pub mod radix;
+#[cfg(feature = "zeroize")]
+mod secret;
+mod solinas;
+mod uint;
 
+/// Fixed bit integers
 macro_rules! define_multiple_uints {
     () => {};
 
-    ($x:literal) => {
+    ($x:literal $(, $xs:literal)*) => {
         paste! {
             #[allow(non_camel_case_types)]
             #[bituint($x)]
-            #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+            #[derive(Clone, Copy, PartialEq, Eq, Hash)]
             pub struct [<u $x>];
+
+            #[cfg(feature = "num-traits")]
+            impl_num_traits!([<u $x>]);
+
+            #[cfg(feature = "rand")]
+            impl_rand!([<u $x>]);
+
+            #[cfg(feature = "serde")]
+            impl_serde!([<u $x>]);
+
+            #[cfg(feature = "subtle")]
+            impl_subtle!([<u $x>]);
+
+            #[cfg(feature = "zeroize")]
+            impl_zeroize!([<u $x>]);
+        }
+
+        define_multiple_uints!($($xs),*);
+    };
+}
+
+define_multiple_uints!(24, 48, 80, 256, 512, 1024, 2048, 4096);
+
+/// Full products into the double width type
+macro_rules! define_wide_muls {
+    ($($x:literal => $y:literal),*) => {
+        paste! {
+            $(
+                impl [<u $x>] {
+                    /// Returns the full product as the double width type.
+                    pub fn mul_wide(self, rhs: [<u $x>]) -> [<u $y>] {
+                        let (low, high) = self.widening_mul(rhs);
+                        let mut bytes = [0u8; [<u $y>]::BYTES];
+
+                        bytes[..[<u $x>]::BYTES].copy_from_slice(&low.to_le_bytes());
+                        bytes[[<u $x>]::BYTES..].copy_from_slice(&high.to_le_bytes());
+
+                        [<u $y>]::from_le_bytes(bytes)
+                    }
+                }
+            )*
+        }
+    };
+}
+
+define_wide_muls!(24 => 48, 256 => 512, 512 => 1024, 1024 => 2048, 2048 => 4096);
+
+#[cfg(feature = "rand")]
+impl_random_prime!(u512, u1024, u2048, u4096);
+
+/// Fixed bit two's complement integers
+macro_rules! define_multiple_ints {
+    () => {};
+
+    ($x:literal) => {
+        paste! {
+            #[allow(non_camel_case_types)]
+            #[bitint($x)]
+            #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+            pub struct [<i $x>];
         }
     };
 
     ($x:literal, $($xs:literal),*) => {
         paste! {
             #[allow(non_camel_case_types)]
-            #[bituint($x)]
-            #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
-            pub struct [<u $x>];
+            #[bitint($x)]
+            #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+            pub struct [<i $x>];
         }
 
-        define_multiple_uints!($($xs),*);
+        define_multiple_ints!($($xs),*);
     };
 }
 
-define_multiple_uints!(24, 48, 80, 256, 512, 1024, 2048, 4096);
+define_multiple_ints!(24, 48, 80, 256, 512, 1024, 2048, 4096);
 
 #[cfg(test)]
 mod tests {
-    use crate::u24;
+    use crate::{i24, i256, i80, u1024, u2048, u24, u256, u4096, u48, u512, u80};
     #[test]
     fn test() {
         assert_eq!(u24::from(5) + u24::from(251), u24::from(256));
         assert_eq!(u24::from(257) - u24::from(251), u24::from(6));
         assert_eq!(u128::from(u24::from(257)), 257);
     }
+
+    #[test]
+    fn overflowing_add_sub() {
+        // wraps around like the primitive types
+        assert_eq!(
+            u256::MAX.overflowing_add(u256::from(2)),
+            (u256::from(1), true)
+        );
+        assert_eq!(u256::MIN.overflowing_sub(u256::from(1)), (u256::MAX, true));
+        assert_eq!(
+            u24::MAX.overflowing_add(u24::MAX),
+            (u24::MAX - u24::from(1), true)
+        );
+        assert_eq!(
+            u80::from(1).overflowing_sub(u80::from(3)),
+            (u80::MAX - u80::from(1), true)
+        );
+        assert_eq!(u256::MAX.overflowing_add(u256::MIN), (u256::MAX, false));
+    }
+
+    #[test]
+    fn mul() {
+        assert_eq!(u24::from(4095) * u24::from(4097), u24::from(4095 * 4097));
+        assert!(u24::from(4096).overflowing_mul(u24::from(4096)).1);
+        assert_eq!(
+            u80::from(u64::MAX).overflowing_mul(u80::from(1u32 << 16)),
+            (u80::from(u64::MAX as u128 * (1 << 16)), false)
+        );
+        assert!(u80::from(u64::MAX).overflowing_mul(u80::from(1u32 << 17)).1);
+
+        let max = u256::from(u128::MAX);
+        let mut expected = [0u8; 32];
+        expected[0] = 1;
+        expected[16..].copy_from_slice(&(u128::MAX - 1).to_le_bytes());
+        assert_eq!(max * max, u256::from_le_bytes(expected));
+        assert!((max * max).overflowing_mul(u256::from(2)).1);
+
+        let mut bytes = [0u8; 512];
+        bytes[256] = 1;
+        let half = u4096::from_le_bytes(bytes);
+        assert!(!half.overflowing_mul(u4096::from(2)).1);
+        assert!(half.overflowing_mul(half).1);
+        assert_eq!(
+            u4096::from(u128::MAX) * u4096::from(u128::MAX),
+            u4096::from(u128::MAX) * u4096::from(u128::MAX - 1) + u4096::from(u128::MAX)
+        );
+    }
+
+    #[test]
+    fn div_rem() {
+        assert_eq!(
+            u24::from(1000).div_rem(u24::from(7)),
+            (u24::from(142), u24::from(6))
+        );
+        assert_eq!(u80::from(u64::MAX) / u80::from(u64::MAX), u80::from(1));
+        assert_eq!(u256::from(5) % u256::from(u128::MAX), u256::from(5));
+
+        let big = u1024::from(u128::MAX) * u1024::from(u128::MAX) * u1024::from(u128::MAX);
+        let rhs = u1024::from(u128::MAX) * u1024::from(u128::MAX - 2);
+        let rem = u1024::from(u128::MAX - 3);
+        let (quot, actual_rem) = (big + rem).div_rem(rhs);
+        assert_eq!(quot * rhs + actual_rem, big + rem);
+
+        let (quot, actual_rem) = (big + rem).div_rem(u1024::from(u128::MAX));
+        assert_eq!(quot, u1024::from(u128::MAX) * u1024::from(u128::MAX));
+        assert_eq!(actual_rem, rem);
+
+        let huge = u4096::from(u128::MAX)
+            .overflowing_mul(u4096::from(u128::MAX))
+            .0;
+        assert_eq!(huge / u4096::from(u128::MAX), u4096::from(u128::MAX));
+
+        assert_eq!(u256::from(1).checked_div_rem(u256::from(0)), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to divide by zero")]
+    fn div_by_zero() {
+        let _ = u2048::from(1) / u2048::from(0);
+    }
+
+    #[test]
+    fn ordering() {
+        let low = u256::from(u128::MAX);
+        let high = u256::from(u128::MAX) + u256::from(1);
+
+        assert!(low < high);
+        assert!(u24::from(0xff00) > u24::from(0xff));
+        assert_eq!(high.cmp(&high), std::cmp::Ordering::Equal);
+
+        let set: std::collections::BTreeSet<_> = [high, low, u256::from(1)].into_iter().collect();
+        assert_eq!(
+            set.into_iter().collect::<Vec<_>>(),
+            [u256::from(1), low, high]
+        );
+    }
+
+    #[test]
+    fn signed() {
+        let minus_one = i256::from(-1);
+        assert!(minus_one.is_negative());
+        assert_eq!(minus_one + i256::from(1), i256::from(0));
+        assert_eq!(-minus_one, i256::from(1));
+        assert_eq!(i256::from(-7) * i256::from(6), i256::from(-42));
+        assert_eq!(
+            i256::from(-7).div_rem(i256::from(2)),
+            (i256::from(-3), i256::from(-1))
+        );
+        assert_eq!(i256::from(-7) >> 1, i256::from(-4));
+        assert_eq!(i256::from(7) >> 1, i256::from(3));
+        assert_eq!(i256::from(-5).abs(), i256::from(5));
+        assert_eq!(i256::from(-5).signum(), minus_one);
+        assert!(i256::from(-5) < i256::from(3));
+        assert!(i256::MIN < i256::MAX);
+
+        assert_eq!(i24::MAX.overflowing_add(i24::from(1)), (i24::MIN, true));
+        assert_eq!(i24::MIN.overflowing_neg(), (i24::MIN, true));
+        assert_eq!(i24::MIN.overflowing_div(i24::from(-1)), (i24::MIN, true));
+        assert_eq!(i24::MIN / i24::from(1), i24::MIN);
+        assert!(!i24::from(-4096).overflowing_mul(i24::from(2048)).1);
+        assert!(i24::from(4096).overflowing_mul(i24::from(2048)).1);
+        assert!(i24::from(-4097).overflowing_mul(i24::from(2048)).1);
+        assert_eq!(i128::from(i24::from(-1000)), -1000);
+        assert_eq!(i64::from(i80::from(i64::MIN)), i64::MIN);
+
+        assert_eq!(u256::try_from(minus_one), Err(crate::TryFromIntError));
+        assert_eq!(u256::try_from(i256::from(5)), Ok(u256::from(5)));
+        assert!(i256::try_from(minus_one.cast_unsigned()).is_err());
+        assert_eq!(i256::cast_signed(u256::from(9)), i256::from(9));
+        assert_eq!(i256::cast_signed(u256::MAX), -i256::ONE);
+
+        // the unsigned type as a path, as downstream crates name it
+        #[allow(non_camel_case_types)]
+        #[crate::bitint(48, crate::u48)]
+        #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+        struct signed48;
+
+        assert_eq!(signed48::cast_signed(u48::MAX), -signed48::ONE);
+        assert_eq!(signed48::MIN.cast_unsigned(), u48::ONE << 47);
+    }
+
+    #[test]
+    fn bitwise() {
+        let a = u256::from(u128::MAX) << 64;
+        assert_eq!(a >> 128, u256::from(u64::MAX));
+        assert_eq!((a >> 64) & u256::from(0xff00u32), u256::from(0xff00u32));
+        assert_eq!(!u256::from(0) ^ a, !a);
+        assert_eq!(u256::from(0xf0u8) | u256::from(0x0fu8), u256::from(0xffu8));
+        assert_eq!(u256::from(1).overflowing_shl(256), (u256::from(1), true));
+        assert_eq!(u256::from(1) << 255 >> 255, u256::from(1));
+
+        assert_eq!(u80::from(1) << 79 >> 79, u80::from(1));
+        assert_eq!(u80::from(3) << 79, u80::from(1) << 79);
+        assert_eq!(u24::from(0xabcdef) >> 12, u24::from(0xabc));
+        assert_eq!(!u24::from(0), u24::from(0xffffff));
+
+        let mut b = u4096::from(1);
+        b <<= 4000;
+        b >>= 3999;
+        assert_eq!(b, u4096::from(2));
+
+        assert_eq!(i256::from(-1) << 4 >> 2, i256::from(-4));
+        assert_eq!(i256::from(-6) & i256::from(0xf), i256::from(10));
+
+        // any primitive integer is an amount, reduced modulo BITS when out of range
+        assert_eq!(u256::ONE << 3usize, u256::from(8));
+        assert_eq!(&u80::from(8) >> 3i8, u80::ONE);
+        assert_eq!(i256::from(-16) >> 2u64, i256::from(-4));
+        assert_eq!(&i24::from(3) << 4i128, i24::from(48));
+        let mut c = i80::from(-1);
+        c <<= 79usize;
+        assert_eq!(c, i80::MIN);
+
+        use crate::ShiftAmount;
+        assert_eq!(300u64.reduce(256), (44, true));
+        assert_eq!((-1i32).reduce(24), (23, true));
+        assert_eq!(u128::MAX.reduce(80), ((u128::MAX % 80) as u32, true));
+        assert_eq!(79isize.reduce(80), (79, false));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "attempt to shift left with overflow")]
+    fn negative_shift() {
+        let _ = u256::ONE << -1;
+    }
+
+    #[test]
+    fn bit_intrinsics() {
+        const ONE_HIGH: u256 = {
+            let mut value = u256::MIN;
+            value.set_bit(200, true);
+            value
+        };
+
+        assert_eq!(ONE_HIGH, u256::from(1) << 200);
+        assert_eq!(ONE_HIGH.leading_zeros(), 55);
+        assert_eq!(ONE_HIGH.trailing_zeros(), 200);
+        assert_eq!(ONE_HIGH.bits(), 201);
+        assert!(ONE_HIGH.bit(200));
+        assert!(!ONE_HIGH.bit(199));
+
+        assert_eq!(u256::MIN.leading_zeros(), 256);
+        assert_eq!(u256::MIN.trailing_zeros(), 256);
+        assert_eq!(u256::MAX.count_ones(), 256);
+        assert_eq!(u2048::from(u128::MAX).count_zeros(), 2048 - 128);
+
+        let mut value = u80::from(u64::MAX);
+        value.set_bit(79, true);
+        value.set_bit(0, false);
+        assert_eq!(value.leading_zeros(), 0);
+        assert_eq!(value.trailing_zeros(), 1);
+        assert_eq!(value.count_ones(), 64);
+        assert_eq!(u24::from(0x10).bits(), 5);
+    }
+
+    #[test]
+    fn formatting() {
+        let value = u256::from(u128::MAX) * u256::from(1000);
+        assert_eq!(
+            value.to_string(),
+            "340282366920938463463374607431768211455000"
+        );
+        assert_eq!(format!("{:?}", u24::from(42)), "42");
+        assert_eq!(format!("{:x?}", u24::from(0xabcdef)), "abcdef");
+        assert_eq!(format!("{:#X?}", u256::from(0xabcdefu32)), "0xABCDEF");
+        assert_eq!(format!("{:x?}", Some(u2048::from(255))), "Some(ff)");
+        assert_eq!(format!("{:>6}", u24::from(42)), "    42");
+        assert_eq!(format!("{:06}", u80::from(42)), "000042");
+        assert_eq!(
+            format!("{:#x}", u256::from(0xdead_beefu32) << 128),
+            "0xdeadbeef00000000000000000000000000000000"
+        );
+        assert_eq!(format!("{:X}", u24::from(0xabcdef)), "ABCDEF");
+        assert_eq!(format!("{:#b}", u48::from(5)), "0b101");
+        assert_eq!(
+            format!("{:o}", u4096::from(u128::MAX)),
+            format!("{:o}", u128::MAX)
+        );
+        assert_eq!(format!("{}", u2048::from(0)), "0");
+
+        assert_eq!(i256::from(-1234).to_string(), "-1234");
+        assert_eq!(format!("{:+}", i24::from(7)), "+7");
+        assert_eq!(format!("{:x}", i24::from(-1)), "ffffff");
+        assert_eq!(format!("{:?}", i80::MIN), i80::MIN.to_string());
+        assert_eq!(format!("{:x?}", i24::from(-1)), "ffffff");
+        assert_eq!(format!("{:X?}", i24::from(0xabc)), "ABC");
+    }
+
+    #[test]
+    fn parsing() {
+        use crate::ParseIntError;
+
+        let max = u256::from(u128::MAX) * u256::from(u128::MAX);
+        assert_eq!(max.to_string().parse::<u256>(), Ok(max));
+        assert_eq!(format!("{:#x}", max).parse::<u256>(), Ok(max));
+        assert_eq!("0b1010".parse::<u24>(), Ok(u24::from(10)));
+        assert_eq!("0o777".parse::<u24>(), Ok(u24::from(0o777)));
+        assert_eq!(u24::from_str_radix("FFFFFF", 16), Ok(u24::from(0xffffff)));
+        assert_eq!(
+            u24::from_str_radix("1000000", 16),
+            Err(ParseIntError::Overflow)
+        );
+        assert_eq!("".parse::<u80>(), Err(ParseIntError::Empty));
+        assert_eq!("12z".parse::<u80>(), Err(ParseIntError::InvalidDigit));
+        assert_eq!("-1".parse::<u80>(), Err(ParseIntError::InvalidDigit));
+
+        assert_eq!("-8388608".parse::<i24>(), Ok(i24::MIN));
+        assert_eq!("-0x10".parse::<i24>(), Ok(i24::from(-16)));
+        assert_eq!("+8388607".parse::<i24>(), Ok(i24::MAX));
+        assert_eq!("8388608".parse::<i24>(), Err(ParseIntError::Overflow));
+        assert_eq!("-".parse::<i24>(), Err(ParseIntError::InvalidDigit));
+        assert_eq!("-+1".parse::<i24>(), Err(ParseIntError::InvalidDigit));
+        assert_eq!(i256::from_str_radix("-zz", 36), Ok(i256::from(-1295)));
+
+        // the sign goes before the prefix
+        assert_eq!("+0xff".parse::<u256>(), Ok(u256::from(255)));
+        assert_eq!("0x+ff".parse::<u256>(), Err(ParseIntError::InvalidDigit));
+        assert_eq!("+0b11".parse::<i24>(), Ok(i24::from(3)));
+        assert_eq!("-0x+10".parse::<i24>(), Err(ParseIntError::InvalidDigit));
+        assert_eq!("-+0x10".parse::<i24>(), Err(ParseIntError::InvalidDigit));
+        assert_eq!("0x".parse::<u24>(), Err(ParseIntError::InvalidDigit));
+    }
+
+    #[test]
+    fn overflow_families() {
+        assert_eq!(u24::MAX.checked_add(u24::from(1)), None);
+        assert_eq!(u24::MAX.wrapping_add(u24::from(2)), u24::from(1));
+        assert_eq!(u24::MAX.saturating_add(u24::from(2)), u24::MAX);
+        assert_eq!(u24::from(1).wrapping_sub(u24::from(2)), u24::MAX);
+        assert_eq!(u24::from(1).saturating_sub(u24::from(2)), u24::MIN);
+        assert_eq!(u24::from(1).wrapping_neg(), u24::MAX);
+        assert_eq!(u24::from(0).checked_neg(), Some(u24::from(0)));
+        assert_eq!(
+            u256::MAX.overflowing_add(u256::MAX),
+            (u256::MAX - u256::from(1), true)
+        );
+        assert_eq!(u256::MIN.overflowing_sub(u256::from(1)), (u256::MAX, true));
+
+        assert_eq!(u256::from(2).pow(255), u256::from(1) << 255);
+        assert_eq!(u256::from(2).checked_pow(256), None);
+        assert_eq!(u256::from(2).wrapping_pow(256), u256::from(0));
+        assert_eq!(u256::from(3).saturating_pow(200), u256::MAX);
+        assert_eq!(u80::from(7).checked_pow(28), Some(u80::from(7u128.pow(28))));
+        assert_eq!(u80::from(7).checked_pow(29), None);
+
+        assert_eq!(u256::from(1).checked_div(u256::from(0)), None);
+        assert_eq!(
+            u256::from(7).checked_rem(u256::from(4)),
+            Some(u256::from(3))
+        );
+        assert_eq!(u256::from(1).checked_shl(256), None);
+        assert_eq!(u256::from(1).wrapping_shl(257), u256::from(2));
+        assert_eq!(u256::MAX.saturating_mul(u256::from(2)), u256::MAX);
+
+        assert_eq!(i24::MAX.saturating_add(i24::from(1)), i24::MAX);
+        assert_eq!(i24::MIN.saturating_sub(i24::from(1)), i24::MIN);
+        assert_eq!(i24::MIN.saturating_mul(i24::from(2)), i24::MIN);
+        assert_eq!(i24::MIN.saturating_div(i24::from(-1)), i24::MAX);
+        assert_eq!(i24::MIN.checked_div(i24::from(-1)), None);
+        assert_eq!(i24::from(-2).pow(23), i24::MIN);
+        assert_eq!(i24::from(-2).saturating_pow(25), i24::MIN);
+        assert_eq!(i24::from(-2).checked_pow(24), None);
+        assert_eq!(i24::from(-1).wrapping_shr(30), i24::from(-1));
+        assert_eq!(i24::MIN.wrapping_neg(), i24::MIN);
+    }
+
+    #[test]
+    fn constants() {
+        const TABLE: [u256; 3] = [u256::ZERO, u256::ONE, u256::MAX];
+        const BYTES: [u8; u2048::BYTES] = [0; u2048::BYTES];
+
+        assert_eq!(TABLE[1] + TABLE[1], u256::from(2));
+        assert_eq!(TABLE[2].count_ones(), u256::BITS);
+        assert_eq!(u2048::from_le_bytes(BYTES), u2048::ZERO);
+        assert_eq!(u24::BYTES, 3);
+        assert_eq!(u80::ONE.trailing_zeros(), 0);
+        assert_eq!(i24::ONE + i24::ONE, i24::from(2));
+        assert_eq!(i256::MAX.cast_unsigned(), u256::MAX >> 1);
+        assert_eq!(i256::ZERO.signum(), i256::ZERO);
+        assert_eq!(i80::BYTES, 10);
+    }
+
+    #[test]
+    fn widening_mul() {
+        assert_eq!(
+            u256::MAX.widening_mul(u256::MAX),
+            (u256::ONE, u256::MAX - u256::ONE)
+        );
+        assert_eq!(
+            u256::MAX.carrying_mul(u256::MAX, u256::MAX),
+            (u256::ZERO, u256::MAX)
+        );
+        assert_eq!(
+            u24::from(0xabcdef).widening_mul(u24::from(0x123456)),
+            (
+                u24::from((0xabcdefu64 * 0x123456) & 0xffffff),
+                u24::from((0xabcdefu64 * 0x123456) >> 24)
+            )
+        );
+        assert_eq!(
+            u80::MAX.widening_mul(u80::from(2)),
+            (u80::MAX - u80::ONE, u80::ONE)
+        );
+
+        assert_eq!(
+            u256::MAX.mul_wide(u256::MAX),
+            u512::from(1) + (((u512::MAX >> 256) - u512::ONE) << 256)
+        );
+        assert_eq!(u24::MAX.mul_wide(u24::from(2)), u48::from(0x1fffffeu32));
+
+        let (low, high) = u2048::MAX.widening_mul(u2048::MAX);
+        assert_eq!((low, high), (u2048::ONE, u2048::MAX - u2048::ONE));
+    }
+
+    #[test]
+    fn pow_mod() {
+        assert_eq!(
+            u24::from(3).pow_mod(u24::from(200), u24::from(1000)),
+            u24::from(1)
+        );
+        assert_eq!(u24::from(7).pow_mod(u24::ZERO, u24::ONE), u24::ZERO);
+        assert_eq!(
+            u80::from(u64::MAX).pow_mod(u80::from(3), u80::from(1u128 << 70)),
+            u80::from((3u128 << 64) - 1)
+        );
+
+        // Fermat over 2^255 - 19, and RSA style round trip modulo p * q
+        let p = (u256::ONE << 255) - u256::from(19);
+        let a = u256::from(u128::MAX) << 100;
+        assert_eq!(a.pow_mod(p - u256::ONE, p), u256::ONE);
+        assert_eq!(a.pow_mod_ct(p - u256::ONE, p), u256::ONE);
+        assert_eq!(a.pow_mod(u256::MAX, p), a.pow_mod_ct(u256::MAX, p));
+
+        let (n, e, d) = (u2048::from(3233), u2048::from(17), u2048::from(2753));
+        let cipher = u2048::from(65).pow_mod(e, n);
+        assert_eq!(cipher, u2048::from(2790));
+        assert_eq!(cipher.pow_mod_ct(d, n), u2048::from(65));
+    }
+
+    #[test]
+    fn modular() {
+        let m = u24::from(1_000_003);
+        let (a, b) = (u24::from(999_999), u24::from(17));
+        assert_eq!(a.add_mod(b, m), u24::from(13));
+        assert_eq!(b.sub_mod(a, m), u24::from(21));
+        assert_eq!(b.neg_mod(m), u24::from(999_986));
+        assert_eq!(u24::ZERO.neg_mod(m), u24::ZERO);
+
+        // unreduced operands give the same result in debug and release builds
+        let big = m + m + u24::from(5);
+        assert_eq!(big.add_mod(a, m), u24::from(1));
+        assert_eq!(b.sub_mod(big, m), u24::from(12));
+        assert_eq!(big.sub_mod(b, m), u24::from(999_991));
+        assert_eq!(big.neg_mod(m), u24::from(999_998));
+        assert_eq!(m.neg_mod(m), u24::ZERO);
+        assert_eq!(
+            u24::MAX.mul_mod(u24::MAX, m),
+            u24::from((((1u64 << 24) - 1).pow(2) % 1_000_003) as u32)
+        );
+
+        // the sum carries out of u256 but the result fits the modulus
+        let p = u256::MAX - u256::from(188);
+        let a = p - u256::ONE;
+        assert_eq!(a.add_mod(a, p), p - u256::from(2));
+        assert_eq!(a.mul_mod(a, p), u256::ONE);
+
+        let inv = a.inv_mod(p).unwrap();
+        assert_eq!(inv.mul_mod(a, p), u256::ONE);
+        let x = u256::from(u128::MAX) << 77;
+        assert_eq!(x.inv_mod(p).unwrap().mul_mod(x, p), u256::ONE);
+        assert_eq!(u256::from(6).inv_mod(u256::from(9)), None);
+        assert_eq!(u256::from(7).inv_mod(u256::from(10)), Some(u256::from(3)));
+        assert_eq!(u256::ZERO.inv_mod(p), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "odd modulus")]
+    fn pow_mod_ct_even_modulus() {
+        u256::from(3).pow_mod_ct(u256::from(5), u256::from(10));
+    }
+
+    #[test]
+    fn constant_time() {
+        let a = u256::from(u128::MAX) << 100;
+        let b = u256::from(12345);
+
+        assert!(bool::from(a.ct_eq(&a)));
+        assert!(!bool::from(a.ct_eq(&b)));
+        assert!(bool::from(b.ct_lt(&a)));
+        assert!(!bool::from(a.ct_lt(&a)));
+        assert!(bool::from(a.ct_gt(&b)));
+        assert_eq!(u256::ct_select(&a, &b, crate::Choice::from(0)), a);
+        assert_eq!(u256::ct_select(&a, &b, crate::Choice::from(1)), b);
+
+        let (mut x, mut y) = (a, b);
+        u256::ct_swap(&mut x, &mut y, crate::Choice::from(1));
+        assert_eq!((x, y), (b, a));
+        u256::ct_swap(&mut x, &mut y, crate::Choice::from(0));
+        assert_eq!((x, y), (b, a));
+
+        let (sum, carry) = u256::MAX.ct_add(u256::from(2));
+        assert_eq!((sum, bool::from(carry)), (u256::ONE, true));
+        let (diff, borrow) = u256::ZERO.ct_sub(u256::ONE);
+        assert_eq!((diff, bool::from(borrow)), (u256::MAX, true));
+        assert_eq!(a.ct_mul(b), a.wrapping_mul(b));
+        assert_eq!(u4096::MAX.ct_mul(u4096::MAX), u4096::ONE);
+        assert_eq!(
+            u80::MAX.ct_mul(u80::from(3)),
+            u80::MAX.wrapping_mul(u80::from(3))
+        );
+    }
 }
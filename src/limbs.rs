@@ -0,0 +1,483 @@
+//! Word level arithmetic shared by the `bituint` generated types.
+//!
+//! Every function works on little-endian `u64` word slices, so the generated
+//! code only has to convert its mixed width limbs into a `[u64; N]` once.
+
+/// Operand size (in words) from which `mul` switches to Karatsuba.
+pub const KARATSUBA_THRESHOLD: usize = 32;
+
+/// Adds `rhs` into `lhs`, propagating the carry through the rest of `lhs`.
+#[inline]
+pub fn add_assign(lhs: &mut [u64], rhs: &[u64]) -> bool {
+    let mut carry = false;
+
+    for (i, word) in lhs.iter_mut().enumerate() {
+        if i >= rhs.len() && !carry {
+            break;
+        }
+
+        let other = rhs.get(i).copied().unwrap_or(0);
+
+        let (sum, c1) = word.overflowing_add(other);
+        let (sum, c2) = sum.overflowing_add(carry as u64);
+        *word = sum;
+        carry = c1 || c2;
+    }
+
+    carry
+}
+
+/// Subtracts `rhs` from `lhs`, propagating the borrow through the rest of `lhs`.
+#[inline]
+pub fn sub_assign(lhs: &mut [u64], rhs: &[u64]) -> bool {
+    let mut borrow = false;
+
+    for (i, word) in lhs.iter_mut().enumerate() {
+        if i >= rhs.len() && !borrow {
+            break;
+        }
+
+        let other = rhs.get(i).copied().unwrap_or(0);
+
+        let (diff, b1) = word.overflowing_sub(other);
+        let (diff, b2) = diff.overflowing_sub(borrow as u64);
+        *word = diff;
+        borrow = b1 || b2;
+    }
+
+    borrow
+}
+
+/// Number of scratch words `mul` needs for `n` word operands.
+pub const fn mul_scratch_len(n: usize) -> usize {
+    if n < KARATSUBA_THRESHOLD {
+        0
+    } else {
+        let m = n - n / 2;
+
+        4 * m + 1 + mul_scratch_len(m)
+    }
+}
+
+/// Writes the full `2 * n` word product of two `n` word operands into `out`.
+///
+/// `scratch` must hold at least `mul_scratch_len(n)` words.
+pub fn mul(out: &mut [u64], lhs: &[u64], rhs: &[u64], scratch: &mut [u64]) {
+    debug_assert_eq!(lhs.len(), rhs.len());
+    debug_assert_eq!(out.len(), lhs.len() + rhs.len());
+
+    if lhs.len() < KARATSUBA_THRESHOLD {
+        schoolbook_mul(out, lhs, rhs);
+    } else {
+        karatsuba_mul(out, lhs, rhs, scratch);
+    }
+}
+
+/// Writes the full product of `lhs` and `rhs` into `out`.
+///
+/// Runs in time independent of the word values, the constant time methods rely on it.
+pub fn schoolbook_mul(out: &mut [u64], lhs: &[u64], rhs: &[u64]) {
+    out.fill(0);
+
+    for (i, &a) in lhs.iter().enumerate() {
+        let mut carry = 0u64;
+
+        for (j, &b) in rhs.iter().enumerate() {
+            let t = a as u128 * b as u128 + out[i + j] as u128 + carry as u128;
+            out[i + j] = t as u64;
+            carry = (t >> 64) as u64;
+        }
+
+        out[i + rhs.len()] = carry;
+    }
+}
+
+fn karatsuba_mul(out: &mut [u64], lhs: &[u64], rhs: &[u64], scratch: &mut [u64]) {
+    let n = lhs.len();
+
+    if n < KARATSUBA_THRESHOLD {
+        schoolbook_mul(out, lhs, rhs);
+        return;
+    }
+
+    let h = n / 2;
+    let m = n - h;
+
+    let (a0, a1) = lhs.split_at(h);
+    let (b0, b1) = rhs.split_at(h);
+
+    let (sa, rest) = scratch.split_at_mut(m);
+    let (sb, rest) = rest.split_at_mut(m);
+    let (mid, rest) = rest.split_at_mut(2 * m + 1);
+
+    // mid = (a0 + a1) * (b0 + b1), keeping the carry words of both sums apart
+    sa.copy_from_slice(a1);
+    let ca = add_assign(sa, a0);
+    sb.copy_from_slice(b1);
+    let cb = add_assign(sb, b0);
+
+    mid[2 * m] = 0;
+    karatsuba_mul(&mut mid[..2 * m], sa, sb, rest);
+
+    if ca {
+        add_assign(&mut mid[m..], sb);
+    }
+
+    if cb {
+        add_assign(&mut mid[m..], sa);
+    }
+
+    if ca && cb {
+        add_assign(&mut mid[2 * m..], &[1]);
+    }
+
+    // out = a1 * b1 * B^(2h) + a0 * b0
+    {
+        let (low, high) = out.split_at_mut(2 * h);
+        karatsuba_mul(low, a0, b0, rest);
+        karatsuba_mul(high, a1, b1, rest);
+    }
+
+    // mid -= a0 * b0 + a1 * b1
+    sub_assign(mid, &out[..2 * h]);
+    sub_assign(mid, &out[2 * h..]);
+
+    add_assign(&mut out[h..], mid);
+}
+
+/// Computes `words = words * mul + add` in place, returning the carry word.
+pub fn mul_word_add(words: &mut [u64], mul: u64, add: u64) -> u64 {
+    let mut carry = add;
+
+    for word in words.iter_mut() {
+        let t = *word as u128 * mul as u128 + carry as u128;
+        *word = t as u64;
+        carry = (t >> 64) as u64;
+    }
+
+    carry
+}
+
+/// Montgomery product `lhs * rhs / 2^(64 * n) mod modulus` (CIOS), with `n =
+/// modulus.len()` and `n_prime = -modulus^-1 mod 2^64`.
+///
+/// All slices are `n` words and `out` must not alias the inputs. The result is
+/// fully reduced whenever `lhs * rhs < modulus * 2^(64 * n)`, which holds if
+/// either operand is below `modulus`. Branch-free for a fixed `n`.
+pub fn mont_mul(out: &mut [u64], lhs: &[u64], rhs: &[u64], modulus: &[u64], n_prime: u64) {
+    let n = modulus.len();
+    // `out` holds the low `n` words of the running sum, `hi` the word above it
+    let mut hi = 0u64;
+
+    out.fill(0);
+
+    for &word in rhs {
+        let mut carry = 0u64;
+
+        for j in 0..n {
+            let t = out[j] as u128 + lhs[j] as u128 * word as u128 + carry as u128;
+            out[j] = t as u64;
+            carry = (t >> 64) as u64;
+        }
+
+        let t = hi as u128 + carry as u128;
+        hi = t as u64;
+        let top = (t >> 64) as u64;
+
+        // add `m * modulus` to clear the lowest word, then shift down a word
+        let m = out[0].wrapping_mul(n_prime);
+        let t = out[0] as u128 + m as u128 * modulus[0] as u128;
+        let mut carry = (t >> 64) as u64;
+
+        for j in 1..n {
+            let t = out[j] as u128 + m as u128 * modulus[j] as u128 + carry as u128;
+            out[j - 1] = t as u64;
+            carry = (t >> 64) as u64;
+        }
+
+        let t = hi as u128 + carry as u128;
+        out[n - 1] = t as u64;
+        hi = top + (t >> 64) as u64;
+    }
+
+    // the sum is below `2 * modulus`: subtract once, add back if that went negative
+    let borrow = sub_assign(out, modulus);
+    let mask = (borrow as u64 & !hi & 1).wrapping_neg();
+    let mut carry = 0u64;
+
+    for (word, &m) in out.iter_mut().zip(modulus) {
+        let t = *word as u128 + (m & mask) as u128 + carry as u128;
+        *word = t as u64;
+        carry = (t >> 64) as u64;
+    }
+}
+
+/// Divides `num` in place by a single word, returning the remainder.
+pub fn div_rem_word(num: &mut [u64], den: u64) -> u64 {
+    let mut rem = 0u64;
+
+    for word in num.iter_mut().rev() {
+        let cur = (rem as u128) << 64 | *word as u128;
+        *word = (cur / den as u128) as u64;
+        rem = (cur % den as u128) as u64;
+    }
+
+    rem
+}
+
+/// Long division (Knuth, TAOCP vol. 2, 4.3.1, algorithm D).
+///
+/// Writes `num / den` into `quot` (at least `num.len()` words) and `num % den`
+/// into `rem` (at least `den.len()` words). `scratch` must hold
+/// `num.len() + den.len() + 1` words. Panics if `den` is zero.
+pub fn div_rem(quot: &mut [u64], rem: &mut [u64], num: &[u64], den: &[u64], scratch: &mut [u64]) {
+    let n = den
+        .iter()
+        .rposition(|&word| word != 0)
+        .expect("attempt to divide by zero")
+        + 1;
+
+    quot.fill(0);
+    rem.fill(0);
+
+    let m = match num.iter().rposition(|&word| word != 0) {
+        Some(idx) => idx + 1,
+        None => return,
+    };
+
+    if m < n {
+        rem[..m].copy_from_slice(&num[..m]);
+        return;
+    }
+
+    if n == 1 {
+        quot[..m].copy_from_slice(&num[..m]);
+        rem[0] = div_rem_word(&mut quot[..m], den[0]);
+        return;
+    }
+
+    // normalize so the top word of the divisor has its high bit set
+    let shift = den[n - 1].leading_zeros();
+    let (v, u) = scratch.split_at_mut(n);
+    let u = &mut u[..m + 1];
+
+    u[m] = shl_into(&mut u[..m], &num[..m], shift);
+    shl_into(v, &den[..n], shift);
+
+    let v_top = v[n - 1] as u128;
+    let v_next = v[n - 2] as u128;
+
+    for j in (0..=m - n).rev() {
+        let top = (u[j + n] as u128) << 64 | u[j + n - 1] as u128;
+        let mut q_hat = top / v_top;
+        let mut r_hat = top % v_top;
+
+        while q_hat > u64::MAX as u128 || q_hat * v_next > (r_hat << 64 | u[j + n - 2] as u128) {
+            q_hat -= 1;
+            r_hat += v_top;
+
+            if r_hat > u64::MAX as u128 {
+                break;
+            }
+        }
+
+        // u[j..=j + n] -= q_hat * v
+        let mut carry = 0u64;
+        let mut borrow = false;
+
+        for i in 0..n {
+            let prod = q_hat * v[i] as u128 + carry as u128;
+            carry = (prod >> 64) as u64;
+
+            let (diff, b1) = u[i + j].overflowing_sub(prod as u64);
+            let (diff, b2) = diff.overflowing_sub(borrow as u64);
+            u[i + j] = diff;
+            borrow = b1 || b2;
+        }
+
+        let (diff, b1) = u[j + n].overflowing_sub(carry);
+        let (diff, b2) = diff.overflowing_sub(borrow as u64);
+        u[j + n] = diff;
+
+        // q_hat was one too large, add the divisor back
+        if b1 || b2 {
+            q_hat -= 1;
+
+            let carry = add_assign(&mut u[j..j + n], v);
+            u[j + n] = u[j + n].wrapping_add(carry as u64);
+        }
+
+        quot[j] = q_hat as u64;
+    }
+
+    shr_into(&mut rem[..n], &u[..n], shift);
+}
+
+/// Shifts `words` left in place by `shift` bits, dropping bits shifted out.
+pub fn shl_assign(words: &mut [u64], shift: u32) {
+    let len = words.len();
+    let word_shift = (shift / 64) as usize;
+    let bit_shift = shift % 64;
+
+    if word_shift >= len {
+        words.fill(0);
+        return;
+    }
+
+    words.copy_within(..len - word_shift, word_shift);
+    words[..word_shift].fill(0);
+
+    if bit_shift != 0 {
+        for i in (word_shift..len).rev() {
+            let carry = if i > word_shift {
+                words[i - 1] >> (64 - bit_shift)
+            } else {
+                0
+            };
+
+            words[i] = words[i] << bit_shift | carry;
+        }
+    }
+}
+
+/// Shifts `words` right in place by `shift` bits, dropping bits shifted out.
+pub fn shr_assign(words: &mut [u64], shift: u32) {
+    let len = words.len();
+    let word_shift = (shift / 64) as usize;
+    let bit_shift = shift % 64;
+
+    if word_shift >= len {
+        words.fill(0);
+        return;
+    }
+
+    words.copy_within(word_shift.., 0);
+    words[len - word_shift..].fill(0);
+
+    if bit_shift != 0 {
+        for i in 0..len - word_shift {
+            let carry = if i + 1 < len - word_shift {
+                words[i + 1] << (64 - bit_shift)
+            } else {
+                0
+            };
+
+            words[i] = words[i] >> bit_shift | carry;
+        }
+    }
+}
+
+/// Writes `src << shift` into `dst` (same length), returning the bits shifted out.
+fn shl_into(dst: &mut [u64], src: &[u64], shift: u32) -> u64 {
+    if shift == 0 {
+        dst.copy_from_slice(src);
+        return 0;
+    }
+
+    let mut carry = 0u64;
+
+    for (d, &s) in dst.iter_mut().zip(src) {
+        *d = s << shift | carry;
+        carry = s >> (64 - shift);
+    }
+
+    carry
+}
+
+/// Writes `src >> shift` into `dst` (same length).
+fn shr_into(dst: &mut [u64], src: &[u64], shift: u32) {
+    if shift == 0 {
+        dst.copy_from_slice(src);
+        return;
+    }
+
+    let mut carry = 0u64;
+
+    for (d, &s) in dst.iter_mut().zip(src).rev() {
+        *d = s >> shift | carry;
+        carry = s << (64 - shift);
+    }
+}
+
+/// Xorshift64 generator for deterministic test operands; `seed` must be nonzero.
+#[cfg(test)]
+pub(crate) fn xorshift(seed: u64) -> impl FnMut() -> u64 {
+    let mut state = seed;
+
+    move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(seed: u64, n: usize) -> Vec<u64> {
+        let mut next = xorshift(seed);
+
+        (0..n).map(|_| next()).collect()
+    }
+
+    #[test]
+    fn karatsuba_matches_schoolbook() {
+        for n in [32, 33, 47, 64, 65] {
+            let a = words(n as u64, n);
+            let b = words(!(n as u64), n);
+
+            let mut expected = vec![0; 2 * n];
+            schoolbook_mul(&mut expected, &a, &b);
+
+            let mut actual = vec![0; 2 * n];
+            let mut scratch = vec![0; mul_scratch_len(n)];
+            mul(&mut actual, &a, &b, &mut scratch);
+
+            assert_eq!(actual, expected);
+
+            let max = vec![u64::MAX; n];
+            schoolbook_mul(&mut expected, &max, &max);
+            mul(&mut actual, &max, &max, &mut scratch);
+
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn shifts() {
+        let mut a = [0x8000_0000_0000_0001, 0x1, 0];
+        shl_assign(&mut a, 65);
+        assert_eq!(a, [0, 0x2, 0x3]);
+        shr_assign(&mut a, 66);
+        assert_eq!(a, [0xc000_0000_0000_0000, 0, 0]);
+        shr_assign(&mut a, 192);
+        assert_eq!(a, [0, 0, 0]);
+    }
+
+    #[test]
+    fn div_rem_inverts_mul() {
+        for (n, d) in [(8, 3), (8, 8), (16, 1), (33, 17), (64, 2)] {
+            let a = words(n as u64, n);
+            let mut b = words(d as u64 + 100, d);
+            let mut c = words(7, d);
+            b[d - 1] >>= 3;
+            c[d - 1] = 0;
+
+            // num = a * b + c with c < b
+            let mut num = vec![0; n + d];
+            schoolbook_mul(&mut num, &a, &b);
+            add_assign(&mut num, &c);
+
+            let mut quot = vec![0; n + d];
+            let mut rem = vec![0; d];
+            let mut scratch = vec![0; 2 * (n + d) + 1];
+            div_rem(&mut quot, &mut rem, &num, &b, &mut scratch);
+
+            assert_eq!(&quot[..n], &a[..]);
+            assert!(quot[n..].iter().all(|&word| word == 0));
+            assert_eq!(rem, c);
+        }
+    }
+}
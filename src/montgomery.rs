@@ -0,0 +1,177 @@
+//! Montgomery multiplication modulo a fixed odd modulus.
+
+use crate::{ct, limbs, pow, BitUint};
+
+/// Precomputed context for arithmetic modulo an odd `modulus`.
+///
+/// Values are kept in Montgomery form `x * R mod modulus` with `R = 2^(64 *
+/// words)`, so a modular product costs one multiplication pass and no division.
+/// Convert in with [`Self::to_montgomery`] and back out with
+/// [`Self::from_montgomery`]; [`Self::mul`], [`Self::square`] and [`Self::pow`]
+/// take and return Montgomery form values below the modulus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Montgomery<T: BitUint> {
+    modulus: T,
+    /// `-modulus^-1 mod 2^64`
+    n_prime: u64,
+    /// `R mod modulus`, one in Montgomery form
+    r: T,
+    /// `R^2 mod modulus`
+    r2: T,
+}
+
+impl<T: BitUint> Montgomery<T> {
+    /// Builds the context, returning `None` if `modulus` is even.
+    pub fn new(modulus: T) -> Option<Montgomery<T>> {
+        let n = modulus.to_words();
+        let n = n.as_ref();
+
+        if n[0] & 1 == 0 {
+            return None;
+        }
+
+        // Newton iteration, each step doubles the number of correct low bits
+        let mut inv = 1u64;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(n[0].wrapping_mul(inv)));
+        }
+
+        let word_bits = 64 * n.len();
+        let mut r = if modulus == T::ONE { T::ZERO } else { T::ONE }.to_words();
+
+        for _ in 0..word_bits {
+            double_mod(r.as_mut(), n);
+        }
+
+        let mut r2 = r;
+
+        for _ in 0..word_bits {
+            double_mod(r2.as_mut(), n);
+        }
+
+        Some(Montgomery {
+            modulus,
+            n_prime: inv.wrapping_neg(),
+            r: T::from_words(r),
+            r2: T::from_words(r2),
+        })
+    }
+
+    /// Builds the context from precomputed parameters, used by `#[prime_field]`
+    /// to evaluate them at compile time.
+    #[doc(hidden)]
+    pub const fn from_raw_parts(modulus: T, n_prime: u64, r: T, r2: T) -> Montgomery<T> {
+        Montgomery {
+            modulus,
+            n_prime,
+            r,
+            r2,
+        }
+    }
+
+    #[inline]
+    pub const fn modulus(&self) -> T {
+        self.modulus
+    }
+
+    /// One in Montgomery form.
+    #[inline]
+    pub const fn one(&self) -> T {
+        self.r
+    }
+
+    /// Converts `x` into Montgomery form, reducing it first if needed.
+    #[inline]
+    pub fn to_montgomery(&self, x: T) -> T {
+        self.mul(x, self.r2)
+    }
+
+    /// Converts a Montgomery form value back to its canonical representative.
+    #[inline]
+    pub fn from_montgomery(&self, x: T) -> T {
+        self.mul(x, T::ONE)
+    }
+
+    /// Montgomery product `a * b / R mod modulus`.
+    pub fn mul(&self, a: T, b: T) -> T {
+        let mut out = T::ZERO.to_words();
+
+        limbs::mont_mul(
+            out.as_mut(),
+            a.to_words().as_ref(),
+            b.to_words().as_ref(),
+            self.modulus.to_words().as_ref(),
+            self.n_prime,
+        );
+
+        T::from_words(out)
+    }
+
+    #[inline]
+    pub fn square(&self, a: T) -> T {
+        self.mul(a, a)
+    }
+
+    /// Raises a Montgomery form `base` to `exp` with a sliding window.
+    ///
+    /// Branches on the bits of `exp`, so only use it with public exponents.
+    pub fn pow(&self, base: T, exp: T) -> T {
+        pow::sliding_window(self.r, base, exp, |a, b| self.mul(a, b))
+    }
+
+    /// Raises a Montgomery form `base` to `exp` with a Montgomery ladder, in
+    /// constant time for secret exponents.
+    pub fn pow_ct(&self, base: T, exp: T) -> T {
+        pow::ladder(self.r, base, exp, |a, b| self.mul(a, b))
+    }
+}
+
+/// `words = 2 * words mod modulus` for `words < modulus`.
+fn double_mod(words: &mut [u64], modulus: &[u64]) {
+    let top = words[words.len() - 1] >> 63;
+
+    limbs::shl_assign(words, 1);
+
+    if top == 1 || !bool::from(ct::lt(words, modulus)) {
+        limbs::sub_assign(words, modulus);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{u24, u256, u80, Montgomery};
+
+    #[test]
+    fn montgomery() {
+        assert!(Montgomery::new(u256::from(10)).is_none());
+
+        let ctx = Montgomery::new(u24::from(1_000_003)).unwrap();
+        let a = ctx.to_montgomery(u24::from(123_456));
+        let b = ctx.to_montgomery(u24::from(16_000_000));
+        assert_eq!(ctx.from_montgomery(a), u24::from(123_456));
+        assert_eq!(
+            ctx.from_montgomery(ctx.mul(a, b)),
+            u24::from((123_456u64 * 16_000_000 % 1_000_003) as u32)
+        );
+        // Fermat: a^(p - 1) = 1
+        assert_eq!(ctx.pow(a, u24::from(1_000_002)), ctx.one());
+
+        let p = (1u128 << 79) - 1;
+        let ctx = Montgomery::new(u80::from(p)).unwrap();
+        let a = ctx.to_montgomery(u80::from(p - 2));
+        assert_eq!(ctx.from_montgomery(ctx.square(a)), u80::from(4));
+
+        // 2^255 - 19
+        let p = (u256::ONE << 255) - u256::from(19);
+        let ctx = Montgomery::new(p).unwrap();
+        let a = ctx.to_montgomery(u256::MAX);
+        assert_eq!(ctx.from_montgomery(a), u256::MAX % p);
+        let inv = ctx.pow(a, p - u256::from(2));
+        assert_eq!(ctx.from_montgomery(ctx.mul(a, inv)), u256::ONE);
+        assert_eq!(ctx.pow(a, u256::ZERO), ctx.one());
+        assert_eq!(ctx.pow_ct(a, p - u256::from(2)), inv);
+
+        let ctx = Montgomery::new(u24::ONE).unwrap();
+        assert_eq!(ctx.to_montgomery(u24::from(5)), u24::ZERO);
+    }
+}
@@ -0,0 +1,124 @@
+//! Exponentiation over an arbitrary multiplication, shared by [`Montgomery`](crate::Montgomery)
+//! and the generated `pow_mod` methods.
+
+use crate::{ct, BitUint, Choice};
+
+/// Largest supported window, so the table of odd powers fits `2^(MAX_WINDOW - 1)` entries.
+const MAX_WINDOW: usize = 6;
+
+#[inline]
+fn bit(words: &[u64], idx: usize) -> u64 {
+    words[idx / 64] >> (idx % 64) & 1
+}
+
+/// Window size minimising squarings plus table multiplications for `bits` bits.
+fn window_size(bits: usize) -> usize {
+    match bits {
+        0..=23 => 1,
+        24..=79 => 3,
+        80..=239 => 4,
+        240..=671 => 5,
+        _ => MAX_WINDOW,
+    }
+}
+
+/// Left to right sliding window exponentiation, where `one` is the identity of
+/// `mul`. Branches and indexes on the bits of `exp`, so it is variable time.
+pub fn sliding_window<T: BitUint>(one: T, base: T, exp: T, mul: impl Fn(T, T) -> T) -> T {
+    let words = exp.to_words();
+    let words = words.as_ref();
+    let bits = match words.iter().rposition(|&word| word != 0) {
+        Some(idx) => 64 * idx + 64 - words[idx].leading_zeros() as usize,
+        None => return one,
+    };
+    let window = window_size(bits);
+
+    // table[i] = base^(2 * i + 1)
+    let mut table = [base; 1 << (MAX_WINDOW - 1)];
+    let square = mul(base, base);
+
+    for i in 1..1 << (window - 1) {
+        table[i] = mul(table[i - 1], square);
+    }
+
+    let mut ret = one;
+    let mut i = bits;
+
+    while i > 0 {
+        if bit(words, i - 1) == 0 {
+            ret = mul(ret, ret);
+            i -= 1;
+            continue;
+        }
+
+        // longest window [low, i) of at most `window` bits ending in a set bit
+        let mut low = i.saturating_sub(window);
+        while bit(words, low) == 0 {
+            low += 1;
+        }
+
+        let mut value = 0;
+        for j in (low..i).rev() {
+            ret = mul(ret, ret);
+            value = value << 1 | bit(words, j) as usize;
+        }
+
+        ret = mul(ret, table[value >> 1]);
+        i = low;
+    }
+
+    ret
+}
+
+/// Montgomery ladder over all `BITS` bits of `exp`, where `one` is the identity
+/// of `mul`. Performs the same sequence of multiplications for every exponent,
+/// so it is constant time whenever `mul` is.
+pub fn ladder<T: BitUint>(one: T, base: T, exp: T, mul: impl Fn(T, T) -> T) -> T {
+    let words = exp.to_words();
+    let mut r0 = one.to_words();
+    let mut r1 = base.to_words();
+
+    for i in (0..T::BITS as usize).rev() {
+        let choice = Choice::from(bit(words.as_ref(), i) as u8);
+
+        // (r0, r1) = if bit { (r0 * r1, r1^2) } else { (r0^2, r0 * r1) }
+        ct::swap(r0.as_mut(), r1.as_mut(), choice);
+
+        let (a, b) = (T::from_words(r0), T::from_words(r1));
+        r1 = mul(a, b).to_words();
+        r0 = mul(a, a).to_words();
+
+        ct::swap(r0.as_mut(), r1.as_mut(), choice);
+    }
+
+    T::from_words(r0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::u80;
+
+    #[test]
+    fn windows_and_ladder() {
+        let modulus = 1_000_000_007u128;
+        let mul = |a: u80, b: u80| u80::from(u128::from(a) * u128::from(b) % modulus);
+
+        for exp in [0u128, 1, 2, 0x1f, 0x20, 0xdead_beef, 1 << 79, (1 << 80) - 1] {
+            let mut expected = 1u128;
+            for i in (0..80).rev() {
+                expected = expected * expected % modulus;
+                if exp >> i & 1 == 1 {
+                    expected = expected * 3 % modulus;
+                }
+            }
+
+            let (base, exp) = (u80::from(3), u80::from(exp));
+            assert_eq!(
+                u128::from(sliding_window(u80::ONE, base, exp, mul)),
+                expected
+            );
+            assert_eq!(u128::from(ladder(u80::ONE, base, exp, mul)), expected);
+        }
+    }
+}
@@ -0,0 +1,686 @@
+//! Primality tests behind the generated `is_probable_prime` and
+//! `is_prime_bpsw` methods, and a Miller-Rabin test with random bases for
+//! candidates an adversary may have chosen.
+
+use crate::{limbs, BitUint, Montgomery};
+
+/// Bound of the trial division sieve.
+const SIEVE_LIMIT: usize = 2048;
+
+const fn sieve() -> [bool; SIEVE_LIMIT] {
+    let mut composite = [false; SIEVE_LIMIT];
+    composite[0] = true;
+    composite[1] = true;
+
+    let mut i = 2;
+    while i * i < SIEVE_LIMIT {
+        if !composite[i] {
+            let mut j = i * i;
+            while j < SIEVE_LIMIT {
+                composite[j] = true;
+                j += i;
+            }
+        }
+        i += 1;
+    }
+
+    composite
+}
+
+const fn small_prime_count() -> usize {
+    let composite = sieve();
+    let mut count = 0;
+    let mut i = 0;
+
+    while i < SIEVE_LIMIT {
+        if !composite[i] {
+            count += 1;
+        }
+        i += 1;
+    }
+
+    count
+}
+
+/// Primes below `SIEVE_LIMIT`, in order.
+pub(crate) const SMALL_PRIMES: [u64; small_prime_count()] = {
+    let composite = sieve();
+    let mut primes = [0; small_prime_count()];
+    let mut len = 0;
+    let mut i = 0;
+
+    while i < SIEVE_LIMIT {
+        if !composite[i] {
+            primes[len] = i as u64;
+            len += 1;
+        }
+        i += 1;
+    }
+
+    primes
+};
+
+/// The value as a `u64`, if it fits.
+fn to_u64<T: BitUint>(n: T) -> Option<u64> {
+    let words = n.to_words();
+    let (low, high) = words.as_ref().split_first().unwrap();
+
+    high.iter().all(|&word| word == 0).then_some(*low)
+}
+
+fn from_u64<T: BitUint>(value: u64) -> T {
+    let mut words = T::ZERO.to_words();
+    words.as_mut()[0] = value;
+
+    T::from_words(words)
+}
+
+/// `n mod p` for every small prime, computed a word sized product of primes at
+/// a time.
+pub(crate) fn small_prime_residues<T: BitUint>(n: T) -> [u64; SMALL_PRIMES.len()] {
+    let mut residues = [0; SMALL_PRIMES.len()];
+    let mut start = 0;
+
+    while start < SMALL_PRIMES.len() {
+        let mut end = start;
+        let mut product = 1u64;
+
+        while let Some(next) = SMALL_PRIMES.get(end).and_then(|&p| product.checked_mul(p)) {
+            product = next;
+            end += 1;
+        }
+
+        let rem = limbs::div_rem_word(n.to_words().as_mut(), product);
+
+        for i in start..end {
+            residues[i] = rem % SMALL_PRIMES[i];
+        }
+
+        start = end;
+    }
+
+    residues
+}
+
+/// Settles `n` by trial division where possible: `Some(false)` for values with a
+/// small factor, `Some(true)` for the rest below `SIEVE_LIMIT^2`, `None`
+/// otherwise.
+fn trial_division<T: BitUint>(n: T) -> Option<bool> {
+    if let Some(small) = to_u64(n) {
+        if small < 2 {
+            return Some(false);
+        }
+
+        if small < SIEVE_LIMIT as u64 {
+            return Some(SMALL_PRIMES.binary_search(&small).is_ok());
+        }
+    }
+
+    if small_prime_residues(n).contains(&0) {
+        return Some(false);
+    }
+
+    match to_u64(n) {
+        Some(small) if small < (SIEVE_LIMIT * SIEVE_LIMIT) as u64 => Some(true),
+        _ => None,
+    }
+}
+
+/// Miller-Rabin with the first `rounds` primes as bases, after trial division.
+///
+/// Deterministic: with 13 rounds no composite below `3.3 * 10^24` passes, but
+/// composites built to pass any fixed set of bases exist. Use [`is_prime_bpsw`]
+/// or, with the `rand` feature, `is_probable_prime_with_rng` for candidates from
+/// untrusted sources.
+///
+/// Panics if `rounds` is zero.
+pub fn is_probable_prime<T: BitUint>(n: T, rounds: u32) -> bool {
+    assert!(rounds > 0, "Miller-Rabin needs at least one round");
+
+    if let Some(prime) = trial_division(n) {
+        return prime;
+    }
+
+    let ctx = Montgomery::new(n).unwrap();
+
+    SMALL_PRIMES
+        .iter()
+        .take(rounds as usize)
+        .all(|&base| miller_rabin(&ctx, from_u64(base)))
+}
+
+/// Miller-Rabin with `rounds` bases drawn uniformly from `[2, n - 2]`, after
+/// trial division.
+///
+/// A composite passes each round with probability at most `1/4`, whatever its
+/// form, so `rounds` of them bound the error by `4^-rounds`.
+///
+/// Panics if `rounds` is zero.
+#[cfg(feature = "rand")]
+pub fn is_probable_prime_with_rng<T, R>(n: T, rounds: u32, rng: &mut R) -> bool
+where
+    T: BitUint,
+    R: rand_core::CryptoRng + rand_core::RngCore + ?Sized,
+{
+    assert!(rounds > 0, "Miller-Rabin needs at least one round");
+
+    if let Some(prime) = trial_division(n) {
+        return prime;
+    }
+
+    let ctx = Montgomery::new(n).unwrap();
+
+    (0..rounds).all(|_| miller_rabin(&ctx, random_base(n, rng)))
+}
+
+/// Uniform base in `[2, n - 2]` for `n` above `SIEVE_LIMIT`, drawn with as many
+/// bits as `n` and rejected when out of range.
+#[cfg(feature = "rand")]
+fn random_base<T, R>(n: T, rng: &mut R) -> T
+where
+    T: BitUint,
+    R: rand_core::CryptoRng + rand_core::RngCore + ?Sized,
+{
+    let n = n.to_words();
+    let bits = 64 * n.as_ref().len() as u32 - leading_zeros(n.as_ref());
+
+    let mut n_minus_one = n;
+    limbs::sub_assign(n_minus_one.as_mut(), &[1]);
+
+    // n has its top bit set, so each draw is accepted with probability over one
+    // half
+    loop {
+        let mut base = T::ZERO.to_words();
+        let w = base.as_mut();
+
+        for word in w.iter_mut().take(bits.div_ceil(64) as usize) {
+            *word = rng.next_u64();
+        }
+
+        if !bits.is_multiple_of(64) {
+            w[(bits / 64) as usize] &= (1 << (bits % 64)) - 1;
+        }
+
+        if cmp_wide(w, &[1]).is_gt() && cmp_wide(w, n_minus_one.as_ref()).is_lt() {
+            return T::from_words(base);
+        }
+    }
+}
+
+/// Baillie-PSW: trial division, a base 2 strong probable prime test and a strong
+/// Lucas probable prime test. No composite is known to pass it, and none below
+/// `2^64` does.
+pub fn is_prime_bpsw<T: BitUint>(n: T) -> bool {
+    if let Some(prime) = trial_division(n) {
+        return prime;
+    }
+
+    let ctx = Montgomery::new(n).unwrap();
+
+    miller_rabin(&ctx, from_u64(2)) && strong_lucas(&ctx)
+}
+
+/// Strong probable prime test to `base`, for odd `n` above `base`.
+fn miller_rabin<T: BitUint>(ctx: &Montgomery<T>, base: T) -> bool {
+    let n = ctx.modulus();
+
+    // n - 1 = d * 2^s with d odd
+    let mut n_minus_one = n.to_words();
+    limbs::sub_assign(n_minus_one.as_mut(), &[1]);
+    let s = trailing_zeros(n_minus_one.as_ref());
+    let mut d = n_minus_one;
+    limbs::shr_assign(d.as_mut(), s);
+
+    let one = ctx.one();
+    let minus_one = ctx.to_montgomery(T::from_words(n_minus_one));
+    let mut x = ctx.pow(ctx.to_montgomery(base), T::from_words(d));
+
+    if x == one || x == minus_one {
+        return true;
+    }
+
+    for _ in 1..s {
+        x = ctx.square(x);
+
+        if x == minus_one {
+            return true;
+        }
+
+        if x == one {
+            return false;
+        }
+    }
+
+    false
+}
+
+/// Strong Lucas probable prime test with Selfridge's parameters: the first `D`
+/// of 5, -7, 9, -11, ... with Jacobi symbol `(D / n) = -1`, `P = 1` and
+/// `Q = (1 - D) / 4`. For odd `n` without small factors.
+fn strong_lucas<T: BitUint>(ctx: &Montgomery<T>) -> bool {
+    let n = ctx.modulus();
+    let mut d = 5i64;
+
+    loop {
+        match jacobi(d, n) {
+            -1 => break,
+            // |d| is below n, so it shares a proper factor with it
+            0 => return false,
+            _ => {}
+        }
+
+        // a square never finds a D, rule it out once the search runs long
+        if d == 13 && is_square(n) {
+            return false;
+        }
+
+        d = if d > 0 { -d - 2 } else { -d + 2 };
+    }
+
+    let signed = |value: i64| {
+        let magnitude = ctx.to_montgomery(from_u64(value.unsigned_abs()));
+
+        if value < 0 {
+            sub_mod(T::ZERO, magnitude, n)
+        } else {
+            magnitude
+        }
+    };
+
+    let big_d = signed(d);
+    let q = signed((1 - d) / 4);
+
+    // n + 1 = k * 2^s with k odd; n + 1 cannot overflow as n = MAX has the
+    // small factor 3 for every whole number of bytes
+    let mut k = n.to_words();
+    limbs::add_assign(k.as_mut(), &[1]);
+    let s = trailing_zeros(k.as_ref());
+    limbs::shr_assign(k.as_mut(), s);
+
+    // U_1 = 1, V_1 = P = 1, Q^1
+    let one = ctx.one();
+    let (mut u, mut v, mut qk) = (one, one, q);
+    let k = k.as_ref();
+    let top = 64 * k.len() as u32 - leading_zeros(k);
+
+    for i in (0..top - 1).rev() {
+        // U_2j = U_j V_j, V_2j = V_j^2 - 2 Q^j
+        u = ctx.mul(u, v);
+        v = sub_mod(ctx.square(v), add_mod(qk, qk, n), n);
+        qk = ctx.square(qk);
+
+        if k[i as usize / 64] >> (i % 64) & 1 == 1 {
+            // U_j+1 = (P U_j + V_j) / 2, V_j+1 = (D U_j + P V_j) / 2
+            let (u_next, v_next) = (add_mod(u, v, n), add_mod(ctx.mul(big_d, u), v, n));
+            u = half_mod(u_next, n);
+            v = half_mod(v_next, n);
+            qk = ctx.mul(qk, q);
+        }
+    }
+
+    if u == T::ZERO || v == T::ZERO {
+        return true;
+    }
+
+    for _ in 1..s {
+        v = sub_mod(ctx.square(v), add_mod(qk, qk, n), n);
+        qk = ctx.square(qk);
+
+        if v == T::ZERO {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Jacobi symbol `(a / n)` for odd `n`.
+fn jacobi<T: BitUint>(a: i64, n: T) -> i32 {
+    let low = n.to_words().as_ref()[0];
+    let magnitude = a.unsigned_abs();
+
+    // (-1 / n) = -1 iff n = 3 mod 4
+    let mut sign = if a < 0 && low & 3 == 3 { -1 } else { 1 };
+
+    // strip factors of two: (2 / n) = -1 iff n = 3, 5 mod 8
+    let twos = magnitude.trailing_zeros();
+    let odd = magnitude >> twos;
+    if twos % 2 == 1 && matches!(low & 7, 3 | 5) {
+        sign = -sign;
+    }
+
+    // reciprocity, both odd: flip iff both are 3 mod 4
+    if odd & 3 == 3 && low & 3 == 3 {
+        sign = -sign;
+    }
+
+    let rem = limbs::div_rem_word(n.to_words().as_mut(), odd);
+
+    sign * jacobi_u64(rem, odd)
+}
+
+/// Jacobi symbol `(a / n)` for odd `n`.
+fn jacobi_u64(mut a: u64, mut n: u64) -> i32 {
+    let mut ret = 1;
+    a %= n;
+
+    while a != 0 {
+        while a.is_multiple_of(2) {
+            a /= 2;
+
+            if matches!(n % 8, 3 | 5) {
+                ret = -ret;
+            }
+        }
+
+        std::mem::swap(&mut a, &mut n);
+
+        if a % 4 == 3 && n % 4 == 3 {
+            ret = -ret;
+        }
+
+        a %= n;
+    }
+
+    if n == 1 {
+        ret
+    } else {
+        0
+    }
+}
+
+/// Whether `n` is a perfect square, building its root a bit at a time.
+fn is_square<T: BitUint>(n: T) -> bool {
+    let bits = 64 * n.to_words().as_ref().len() as u32 - leading_zeros(n.to_words().as_ref());
+    let mut root = T::ZERO.to_words();
+
+    for i in (0..bits.div_ceil(2)).rev() {
+        root.as_mut()[i as usize / 64] |= 1 << (i % 64);
+
+        let square = T::from_words(root).widening_mul_words(T::from_words(root));
+        if cmp_wide(square.as_ref(), n.to_words().as_ref()) == std::cmp::Ordering::Greater {
+            root.as_mut()[i as usize / 64] &= !(1 << (i % 64));
+        }
+    }
+
+    let root = T::from_words(root);
+    cmp_wide(
+        root.widening_mul_words(root).as_ref(),
+        n.to_words().as_ref(),
+    ) == std::cmp::Ordering::Equal
+}
+
+/// Compares little-endian word slices of possibly different lengths.
+fn cmp_wide(lhs: &[u64], rhs: &[u64]) -> std::cmp::Ordering {
+    let len = lhs.len().max(rhs.len());
+
+    (0..len)
+        .rev()
+        .map(|i| {
+            let a = lhs.get(i).copied().unwrap_or(0);
+            let b = rhs.get(i).copied().unwrap_or(0);
+
+            a.cmp(&b)
+        })
+        .find(|ord| ord.is_ne())
+        .unwrap_or(std::cmp::Ordering::Equal)
+}
+
+fn trailing_zeros(words: &[u64]) -> u32 {
+    let idx = words.iter().position(|&word| word != 0).unwrap();
+
+    64 * idx as u32 + words[idx].trailing_zeros()
+}
+
+fn leading_zeros(words: &[u64]) -> u32 {
+    match words.iter().rposition(|&word| word != 0) {
+        Some(idx) => 64 * (words.len() - 1 - idx) as u32 + words[idx].leading_zeros(),
+        None => 64 * words.len() as u32,
+    }
+}
+
+/// `a + b mod n` for `a, b < n`.
+fn add_mod<T: BitUint>(a: T, b: T, n: T) -> T {
+    let mut words = a.to_words();
+    let carry = limbs::add_assign(words.as_mut(), b.to_words().as_ref());
+
+    if carry || cmp_wide(words.as_ref(), n.to_words().as_ref()).is_ge() {
+        limbs::sub_assign(words.as_mut(), n.to_words().as_ref());
+    }
+
+    T::from_words(words)
+}
+
+/// `a - b mod n` for `a, b < n`.
+fn sub_mod<T: BitUint>(a: T, b: T, n: T) -> T {
+    let mut words = a.to_words();
+
+    if limbs::sub_assign(words.as_mut(), b.to_words().as_ref()) {
+        limbs::add_assign(words.as_mut(), n.to_words().as_ref());
+    }
+
+    T::from_words(words)
+}
+
+/// `a / 2 mod n` for odd `n` and `a < n`.
+fn half_mod<T: BitUint>(a: T, n: T) -> T {
+    let mut words = a.to_words();
+    let w = words.as_mut();
+    let mut carry = false;
+
+    if w[0] & 1 == 1 {
+        carry = limbs::add_assign(w, n.to_words().as_ref());
+    }
+
+    limbs::shr_assign(w, 1);
+    let last = w.len() - 1;
+    w[last] |= (carry as u64) << 63;
+
+    T::from_words(words)
+}
+
+/// Width of the incremental search from each random starting point.
+#[cfg(feature = "rand")]
+const SEARCH_WINDOW: u64 = 1 << 20;
+
+/// Draws a random `bits` bit prime with its top two bits set, or a safe prime
+/// `p = 2q + 1` with `q` prime too if `safe` is set.
+///
+/// Each random odd start is advanced through a window of candidates, updating
+/// its residues modulo the small primes instead of dividing again, and only
+/// survivors of the sieve reach Baillie-PSW.
+#[cfg(feature = "rand")]
+pub fn random_prime<T, R>(bits: u32, safe: bool, rng: &mut R) -> T
+where
+    T: BitUint,
+    R: rand_core::CryptoRng + rand_core::RngCore + ?Sized,
+{
+    assert!(
+        (16..=T::BITS).contains(&bits),
+        "bit length out of range for a random prime"
+    );
+
+    // safe primes are 3 mod 4 so that q is odd, and stay so in steps of 4
+    let step = if safe { 4 } else { 2 };
+
+    loop {
+        let mut words = T::ZERO.to_words();
+        let w = words.as_mut();
+
+        for word in w.iter_mut().take(bits.div_ceil(64) as usize) {
+            *word = rng.next_u64();
+        }
+
+        let top = (bits - 1) as usize;
+        if !bits.is_multiple_of(64) {
+            w[top / 64] &= (1 << (bits % 64)) - 1;
+        }
+        w[top / 64] |= 1 << (top % 64);
+        w[(top - 1) / 64] |= 1 << ((top - 1) % 64);
+        w[0] |= if safe { 3 } else { 1 };
+
+        let start = T::from_words(words);
+        let residues = small_prime_residues(start);
+
+        let mut delta = 0;
+        while delta < SEARCH_WINDOW {
+            let sieved = SMALL_PRIMES.iter().zip(&residues).all(|(&p, &r)| {
+                let r = (r + delta) % p;
+                // a residue of one means the small prime divides q
+                r != 0 && !(safe && r == 1 && p != 2)
+            });
+
+            if sieved {
+                let mut candidate = start.to_words();
+                limbs::add_assign(candidate.as_mut(), &[delta]);
+
+                // stepping past 2^bits loses the top bits, start over
+                if 64 * candidate.as_ref().len() as u32 - leading_zeros(candidate.as_ref()) > bits {
+                    break;
+                }
+
+                let candidate = T::from_words(candidate);
+                let prime = if safe {
+                    let mut q = candidate.to_words();
+                    limbs::shr_assign(q.as_mut(), 1);
+
+                    is_prime_bpsw(T::from_words(q)) && is_prime_bpsw(candidate)
+                } else {
+                    is_prime_bpsw(candidate)
+                };
+
+                if prime {
+                    return candidate;
+                }
+            }
+
+            delta += step;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{u1024, u24, u256};
+
+    #[test]
+    fn helpers() {
+        assert_eq!(SMALL_PRIMES.len(), 309);
+        assert_eq!(SMALL_PRIMES[..5], [2, 3, 5, 7, 11]);
+        assert_eq!(SMALL_PRIMES[308], 2039);
+
+        assert_eq!(jacobi_u64(1001, 9907), -1);
+        assert_eq!(jacobi_u64(19, 45), 1);
+        assert_eq!(jacobi_u64(8, 21), -1);
+        assert_eq!(jacobi(-7, u256::from(15)), jacobi_u64(8, 15));
+        assert_eq!(jacobi(-11, u256::from(101)), jacobi_u64(90, 101));
+
+        let root = u256::from(u128::MAX);
+        assert!(is_square(root * root));
+        assert!(!is_square(root * root - u256::ONE));
+    }
+
+    #[test]
+    fn primality() {
+        let primes = [
+            u256::from(2),
+            u256::from(2039),
+            u256::from(1_000_000_007),
+            u256::from(u64::MAX - 58),
+            (u256::ONE << 255) - u256::from(19),
+            u256::MAX - (u256::ONE << 32) - u256::from(976),
+        ];
+
+        for p in primes {
+            assert!(p.is_probable_prime(20), "{}", p);
+            assert!(p.is_prime_bpsw(), "{}", p);
+        }
+
+        let composites = [
+            u256::ZERO,
+            u256::ONE,
+            u256::from(2047),
+            // Carmichael number
+            u256::from(561),
+            u256::from(1_000_000_007u64) * u256::from(1_000_000_009u64),
+            // square of a prime, which never finds a Lucas parameter
+            ((u256::ONE << 61) - u256::ONE) * ((u256::ONE << 61) - u256::ONE),
+            (u256::ONE << 255) - u256::from(21),
+        ];
+
+        for n in composites {
+            assert!(!n.is_probable_prime(20), "{}", n);
+            assert!(!n.is_prime_bpsw(), "{}", n);
+        }
+
+        // strong pseudoprime to the first eleven prime bases, but not the twelfth
+        let psi = u256::from(3_825_123_056_546_413_051u64);
+        assert!(psi.is_probable_prime(11));
+        assert!(!psi.is_probable_prime(12));
+        assert!(!psi.is_prime_bpsw());
+
+        // strong Lucas pseudoprime, caught by the base 2 round
+        let ctx = Montgomery::new(u256::from(5777)).unwrap();
+        assert!(strong_lucas(&ctx));
+        assert!(!miller_rabin(&ctx, u256::from(2)));
+
+        // 2^521 - 1 is prime; 2^523 - 1 has no small factor and, like every
+        // composite Mersenne number with a prime exponent, passes base 2
+        let m521 = (u1024::ONE << 521) - u1024::ONE;
+        let m523 = (u1024::ONE << 523) - u1024::ONE;
+        assert!(m521.is_probable_prime(5));
+        assert!(m521.is_prime_bpsw());
+        assert!(m523.is_probable_prime(1));
+        assert!(!m523.is_probable_prime(2));
+        assert!(!m523.is_prime_bpsw());
+
+        assert!(u24::from(8_388_593).is_prime_bpsw());
+        assert!(!u24::from(8_388_591).is_prime_bpsw());
+        assert!(!u24::MAX.is_prime_bpsw());
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    fn random_bases() {
+        use rand_chacha::rand_core::SeedableRng;
+
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(0x5eed);
+
+        let p = (u256::ONE << 255) - u256::from(19);
+        assert!(is_probable_prime_with_rng(p, 20, &mut rng));
+        assert!(is_probable_prime_with_rng(
+            u256::from(1_000_000_007),
+            1,
+            &mut rng
+        ));
+
+        let n = u256::from(1_000_000_007u64) * u256::from(1_000_000_009u64);
+        assert!(!is_probable_prime_with_rng(n, 20, &mut rng));
+        assert!(!is_probable_prime_with_rng(u256::from(561), 20, &mut rng));
+
+        // passes the first eleven prime bases, but not random ones
+        let psi = u256::from(3_825_123_056_546_413_051u64);
+        assert!(!is_probable_prime_with_rng(psi, 20, &mut rng));
+
+        let m523 = (u1024::ONE << 523) - u1024::ONE;
+        assert!(!is_probable_prime_with_rng(m523, 20, &mut rng));
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one round")]
+    fn zero_rounds() {
+        let n = u256::from(1_000_000_007u64) * u256::from(1_000_000_009u64);
+        n.is_probable_prime(0);
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    #[should_panic(expected = "at least one round")]
+    fn zero_rounds_with_rng() {
+        use rand_chacha::rand_core::SeedableRng;
+
+        let n = u256::from(1_000_000_007u64) * u256::from(1_000_000_009u64);
+        is_probable_prime_with_rng(n, 0, &mut rand_chacha::ChaCha8Rng::seed_from_u64(0));
+    }
+}
@@ -0,0 +1,187 @@
+//! Digit conversion shared by the formatting and parsing impls of the generated types.
+
+use crate::{limbs, ParseIntError};
+
+/// Largest power of ten that fits a word, used to peel off 19 digits per division.
+const DECIMAL_CHUNK: u64 = 10_000_000_000_000_000_000;
+
+/// Formats little-endian `words` as decimal digits, without leading zeros.
+pub fn to_decimal(words: &[u64]) -> String {
+    let mut words = words.to_vec();
+    let mut chunks = vec![];
+
+    while words.iter().any(|&word| word != 0) {
+        chunks.push(limbs::div_rem_word(&mut words, DECIMAL_CHUNK));
+    }
+
+    match chunks.split_last() {
+        None => "0".into(),
+        Some((top, rest)) => {
+            let mut ret = top.to_string();
+
+            for chunk in rest.iter().rev() {
+                ret.push_str(&format!("{:019}", chunk));
+            }
+
+            ret
+        }
+    }
+}
+
+/// Formats little-endian `words` in base `2^digit_bits`, without leading zeros.
+pub fn to_pow2_radix(words: &[u64], digit_bits: u32, upper: bool) -> String {
+    let bits = words.len() as u32 * 64;
+    let digits = bits.div_ceil(digit_bits);
+    let mut ret = String::new();
+
+    for digit in (0..digits).rev() {
+        let value = bits_at(words, digit * digit_bits, digit_bits);
+
+        if value == 0 && ret.is_empty() && digit != 0 {
+            continue;
+        }
+
+        let c = std::char::from_digit(value as u32, 1 << digit_bits).unwrap();
+        ret.push(if upper { c.to_ascii_uppercase() } else { c });
+    }
+
+    ret
+}
+
+/// Splits an optional `+` sign and then a `0x`, `0o` or `0b` prefix off `src`,
+/// returning the digits and their radix. A further sign after either is invalid.
+pub fn split_prefix(src: &str) -> Result<(&str, u32), ParseIntError> {
+    let unsigned = src.strip_prefix('+').unwrap_or(src);
+
+    let (digits, radix) = if let Some(digits) = unsigned.strip_prefix("0x") {
+        (digits, 16)
+    } else if let Some(digits) = unsigned.strip_prefix("0o") {
+        (digits, 8)
+    } else if let Some(digits) = unsigned.strip_prefix("0b") {
+        (digits, 2)
+    } else {
+        (unsigned, 10)
+    };
+
+    if src.is_empty() {
+        Err(ParseIntError::Empty)
+    } else if digits.is_empty() || digits.starts_with(['+', '-']) {
+        Err(ParseIntError::InvalidDigit)
+    } else {
+        Ok((digits, radix))
+    }
+}
+
+/// Parses an optionally `+` prefixed string of digits into `words`, failing if the
+/// value needs more than `bits` bits.
+///
+/// # Panics
+///
+/// Panics if `radix` is not in the range `2..=36`.
+pub fn parse(words: &mut [u64], bits: u32, src: &str, radix: u32) -> Result<(), ParseIntError> {
+    assert!(
+        (2..=36).contains(&radix),
+        "from_str_radix: radix must lie in the range `[2, 36]` - found {}",
+        radix
+    );
+
+    if src.is_empty() {
+        return Err(ParseIntError::Empty);
+    }
+
+    let digits = src.strip_prefix('+').unwrap_or(src);
+
+    if digits.is_empty() {
+        return Err(ParseIntError::InvalidDigit);
+    }
+
+    words.fill(0);
+
+    for c in digits.chars() {
+        let digit = c.to_digit(radix).ok_or(ParseIntError::InvalidDigit)?;
+
+        if limbs::mul_word_add(words, radix as u64, digit as u64) != 0 {
+            return Err(ParseIntError::Overflow);
+        }
+    }
+
+    let top = words.len() as u32 * 64;
+
+    if top > bits && words[words.len() - 1] >> (64 - (top - bits)) != 0 {
+        return Err(ParseIntError::Overflow);
+    }
+
+    Ok(())
+}
+
+/// Reads `len` (< 64) bits starting at bit `start`, treating missing words as zero.
+fn bits_at(words: &[u64], start: u32, len: u32) -> u64 {
+    let word = (start / 64) as usize;
+    let shift = start % 64;
+
+    let mut value = words.get(word).copied().unwrap_or(0) >> shift;
+
+    if shift + len > 64 {
+        value |= words.get(word + 1).copied().unwrap_or(0) << (64 - shift);
+    }
+
+    value & ((1 << len) - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digits() {
+        assert_eq!(to_decimal(&[0, 0]), "0");
+        assert_eq!(to_decimal(&[0, 1]), (1u128 << 64).to_string());
+        assert_eq!(to_decimal(&[u64::MAX, u64::MAX]), u128::MAX.to_string());
+        assert_eq!(to_pow2_radix(&[0xbeef, 0x1], 4, true), "1000000000000BEEF");
+        assert_eq!(
+            to_pow2_radix(&[0, u64::MAX], 3, false),
+            format!("{:o}", (u64::MAX as u128) << 64)
+        );
+        assert_eq!(to_pow2_radix(&[0], 1, false), "0");
+    }
+
+    #[test]
+    fn parsing() {
+        let mut words = [0; 2];
+
+        assert_eq!(parse(&mut words, 128, &u128::MAX.to_string(), 10), Ok(()));
+        assert_eq!(words, [u64::MAX, u64::MAX]);
+        assert_eq!(parse(&mut words, 72, "+fFfF", 16), Ok(()));
+        assert_eq!(words, [0xffff, 0]);
+        assert_eq!(parse(&mut words, 72, "100000000000000000", 16), Ok(()));
+        assert_eq!(
+            parse(&mut words, 72, "1000000000000000000", 16),
+            Err(ParseIntError::Overflow)
+        );
+        assert_eq!(
+            parse(&mut words, 128, "100000000000000000000000000000000", 16),
+            Err(ParseIntError::Overflow)
+        );
+        assert_eq!(parse(&mut words, 128, "", 10), Err(ParseIntError::Empty));
+        assert_eq!(
+            parse(&mut words, 128, "+", 10),
+            Err(ParseIntError::InvalidDigit)
+        );
+        assert_eq!(
+            parse(&mut words, 128, "12a", 10),
+            Err(ParseIntError::InvalidDigit)
+        );
+        assert_eq!(split_prefix("0b101"), Ok(("101", 2)));
+        assert_eq!(split_prefix("+0xff"), Ok(("ff", 16)));
+        assert_eq!(split_prefix("+12"), Ok(("12", 10)));
+        assert_eq!(split_prefix(""), Err(ParseIntError::Empty));
+        for invalid in ["0x+ff", "0x-ff", "++12", "+-12", "+", "0o", "+0b"] {
+            assert_eq!(
+                split_prefix(invalid),
+                Err(ParseIntError::InvalidDigit),
+                "{}",
+                invalid
+            );
+        }
+    }
+}
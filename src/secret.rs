@@ -0,0 +1,100 @@
+//! Wrapper for key material that is wiped once dropped.
+
+use std::fmt;
+
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Owns a secret value, such as a private exponent, and zeroizes it on drop.
+///
+/// The generated types are `Copy`, so every move of a bare `u2048` may leave
+/// a stale copy behind. `Secret` is not `Copy`, keeps the one value it owns in
+/// place and wipes it when dropped. Its `Debug` output omits the value and it
+/// has no `Display`.
+///
+/// Values read through [`Self::expose_secret`] are ordinary copies again, so
+/// keep them short lived.
+pub struct Secret<T: Zeroize>(T);
+
+impl<T: Zeroize> Secret<T> {
+    #[inline]
+    pub fn new(value: T) -> Secret<T> {
+        Secret(value)
+    }
+
+    #[inline]
+    pub fn expose_secret(&self) -> &T {
+        &self.0
+    }
+
+    /// Mutable access, so the value can be updated in place.
+    #[inline]
+    pub fn expose_secret_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: Zeroize> From<T> for Secret<T> {
+    #[inline]
+    fn from(value: T) -> Secret<T> {
+        Secret(value)
+    }
+}
+
+impl<T: Zeroize + Clone> Clone for Secret<T> {
+    #[inline]
+    fn clone(&self) -> Secret<T> {
+        Secret(self.0.clone())
+    }
+}
+
+impl<T: Zeroize> Drop for Secret<T> {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<T: Zeroize> ZeroizeOnDrop for Secret<T> {}
+
+impl<T: Zeroize> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret(..)")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use zeroize::Zeroize;
+
+    use crate::{u2048, u256, Secret};
+
+    /// Records being zeroized, which a dropped value cannot be inspected for.
+    struct Probe(Rc<Cell<bool>>);
+
+    impl Zeroize for Probe {
+        fn zeroize(&mut self) {
+            self.0.set(true);
+        }
+    }
+
+    #[test]
+    fn zeroize() {
+        let mut value = u256::MAX;
+        value.zeroize();
+        assert_eq!(value, u256::ZERO);
+
+        let mut secret = Secret::new(u2048::MAX - u2048::ONE);
+        assert_eq!(*secret.expose_secret(), u2048::MAX - u2048::ONE);
+        *secret.expose_secret_mut() += u2048::ONE;
+        assert_eq!(*secret.expose_secret(), u2048::MAX);
+        assert_eq!(format!("{:?}", secret), "Secret(..)");
+
+        let wiped = Rc::new(Cell::new(false));
+        let secret = Secret::from(Probe(wiped.clone()));
+        assert!(!wiped.get());
+        drop(secret);
+        assert!(wiped.get());
+    }
+}
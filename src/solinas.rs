@@ -0,0 +1,475 @@
+//! Reduction modulo special form moduli `2^k - c`.
+
+use crate::{ct, field, limbs, pow, BitUint, Choice};
+use std::cmp::Ordering;
+
+/// Most signed power of two terms a table row may have.
+const MAX_TERMS: usize = 16;
+/// Largest modulus and type the table form handles, in words.
+const TABLE_WORDS: usize = 9;
+/// Most 32 bit digits above `2^k` the table form folds, `(2 * BITS - k) / 32`
+/// for any `k` in a type of `TABLE_WORDS` words.
+const MAX_ROWS: usize = 4 * TABLE_WORDS;
+/// 32 bit accumulator columns, enough for a folded value below `2^(k + 64)`.
+const COLUMNS: usize = 2 * TABLE_WORDS + 2;
+
+/// `2^(k + 32 j) mod modulus` as `(negative, e)` signed powers of two.
+type Row = ([(bool, u32); MAX_TERMS], usize);
+
+// boxing the table would rule out building it in a const
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Form {
+    /// `c` fits a word and is folded in with a single word multiplication.
+    Word(u64),
+    /// Each 32 bit digit above `2^k` is folded in with the shifts of its row.
+    Table([Row; MAX_ROWS]),
+}
+
+/// Precomputed context for arithmetic modulo `modulus = 2^k - c`.
+///
+/// Covers pseudo-Mersenne moduli with a word sized `c` such as `2^255 - 19` and
+/// `2^256 - 2^32 - 977`, and Solinas moduli in types of up to 576 bits such as
+/// P-256 and P-384 in `u512`, where every `2^(k + 32 j) mod modulus` is a short
+/// sum of signed powers of two. A double width value is reduced by folding the bits above `2^k` back
+/// in, without division or Montgomery form. The folds only depend on the
+/// modulus, so reduction takes the same time for every value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Solinas<T: BitUint> {
+    modulus: T,
+    k: u32,
+    c_bits: u32,
+    form: Form,
+    folds: u32,
+}
+
+impl<T: BitUint> Solinas<T> {
+    /// Builds the context, returning `None` unless `c < 2^(k - 2)` and `c` either
+    /// fits a word or the modulus has the sparse table form described above.
+    pub fn new(modulus: T) -> Option<Solinas<T>> {
+        Solinas::with_words(modulus, modulus.to_words().as_ref())
+    }
+
+    /// [`Self::new`] with the words of `modulus` passed in, so `#[prime_field]`
+    /// can build the context at compile time.
+    #[doc(hidden)]
+    pub const fn with_words(modulus: T, words: &[u64]) -> Option<Solinas<T>> {
+        let mut top = words.len();
+        while top > 0 && words[top - 1] == 0 {
+            top -= 1;
+        }
+
+        if top == 0 {
+            return None;
+        }
+
+        let k = 64 * top as u32 - words[top - 1].leading_zeros();
+        let mut c_bits = k;
+        while c_bits > 0 && c_bit(words, k, c_bits - 1) == 0 {
+            c_bits -= 1;
+        }
+
+        // every fold has to shrink the value
+        if c_bits + 2 > k {
+            return None;
+        }
+
+        let form = if c_bits <= 64 {
+            let c = words[0].wrapping_neg();
+
+            Form::Word(if k < 64 { c & ((1 << k) - 1) } else { c })
+        } else {
+            match table(words, top, k, 2 * T::BITS) {
+                Some(rows) => Form::Table(rows),
+                None => return None,
+            }
+        };
+
+        let mut ctx = Solinas {
+            modulus,
+            k,
+            c_bits,
+            form,
+            folds: 1,
+        };
+
+        // once below 2^(k + 1), one more fold leaves less than 2^k + c < 2 * modulus
+        let mut bits = 2 * T::BITS;
+        while bits > k + 1 {
+            bits = ctx.next_bits(bits);
+            ctx.folds += 1;
+        }
+
+        Some(ctx)
+    }
+
+    /// Bound on the bit length of a value below `2^bits` after one fold.
+    const fn next_bits(&self, bits: u32) -> u32 {
+        let k = self.k;
+
+        match self.form {
+            // lo + hi * c
+            Form::Word(_) => max(k, bits - k + self.c_bits) + 1,
+            // lo + sum of 32 bit digits times their row, each row below the
+            // modulus and the first one equal to c
+            Form::Table(_) => {
+                let digits = (bits - k).div_ceil(32);
+                let mut top = min(32, bits - k) + self.c_bits;
+
+                if digits > 1 {
+                    top = max(top, min(32, bits - k - 32) + k);
+                }
+
+                max(k, top) + (u32::BITS - digits.leading_zeros())
+            }
+        }
+    }
+
+    #[inline]
+    pub const fn modulus(&self) -> T {
+        self.modulus
+    }
+
+    /// Reduces `high * 2^BITS + low`, as returned by `widening_mul`.
+    pub fn reduce(&self, low: T, high: T) -> T {
+        let mut wide = low.widening_mul_words(T::ONE);
+        let mut top = high.widening_mul_words(T::ONE);
+
+        limbs::shl_assign(top.as_mut(), T::BITS);
+        limbs::add_assign(wide.as_mut(), top.as_ref());
+
+        self.reduce_words(wide)
+    }
+
+    /// Reduces a full product, as returned by `widening_mul_words`.
+    pub fn reduce_words(&self, mut wide: T::WideWords) -> T {
+        let x = wide.as_mut();
+        let mut bits = 2 * T::BITS;
+
+        for _ in 0..self.folds {
+            match &self.form {
+                Form::Word(c) => {
+                    // only the words the bound allows can be non-zero
+                    let len = (bits.div_ceil(64) as usize).min(x.len());
+
+                    fold_word(&mut x[..len], self.k, *c);
+                }
+                Form::Table(rows) => self.fold_table(x, bits, rows),
+            }
+
+            bits = self.next_bits(bits);
+        }
+
+        // now below 2 * modulus, possibly with bit k set in the word above `ret`
+        let mut ret = self.modulus.to_words();
+        let n = ret.as_ref().len();
+        ret.as_mut().copy_from_slice(&x[..n]);
+
+        let mut diff = ret;
+        let borrow = limbs::sub_assign(diff.as_mut(), self.modulus.to_words().as_ref());
+        let above = x.get(n).copied().unwrap_or(0);
+        let choice = Choice::from((above as u8 & 1) | !borrow as u8);
+        ct::assign(ret.as_mut(), diff.as_ref(), choice);
+
+        T::from_words(ret)
+    }
+
+    /// Replaces `x < 2^bits` by the low `k` bits plus every 32 bit digit above
+    /// them times its row, summed in signed 32 bit columns.
+    fn fold_table(&self, x: &mut [u64], bits: u32, rows: &[Row; MAX_ROWS]) {
+        let k = self.k;
+        let low_cols = k.div_ceil(32) as usize;
+        // columns the terms and the folded value can reach
+        let cols = max(low_cols as u32 + 1, self.next_bits(bits).div_ceil(32)) as usize;
+        let mut acc = [0i64; COLUMNS];
+
+        for (col, acc) in acc[..low_cols].iter_mut().enumerate() {
+            *acc = (x[col / 2] >> (32 * (col % 2)) & 0xffff_ffff) as i64;
+        }
+
+        if !k.is_multiple_of(32) {
+            acc[low_cols - 1] &= (1 << (k % 32)) - 1;
+        }
+
+        let digits = (bits - k).div_ceil(32) as usize;
+
+        for (j, (terms, len)) in rows[..digits].iter().enumerate() {
+            let digit = read_u32(x, k + 32 * j as u32);
+
+            for &(negative, e) in &terms[..*len] {
+                let value = digit << (e % 32);
+                let col = e as usize / 32;
+                let (low, high) = ((value & 0xffff_ffff) as i64, (value >> 32) as i64);
+
+                if negative {
+                    acc[col] -= low;
+                    acc[col + 1] -= high;
+                } else {
+                    acc[col] += low;
+                    acc[col + 1] += high;
+                }
+            }
+        }
+
+        // the sum is non-negative, so the final carry is zero
+        let mut carry = 0i64;
+        x[..bits.div_ceil(64) as usize].fill(0);
+
+        for (col, &acc) in acc[..cols].iter().enumerate() {
+            let t = acc + carry;
+            carry = t >> 32;
+            x[col / 2] |= (t as u64 & 0xffff_ffff) << (32 * (col % 2));
+        }
+    }
+
+    /// `a * b mod modulus`, for any operands.
+    #[inline]
+    pub fn mul(&self, a: T, b: T) -> T {
+        self.reduce_words(a.widening_mul_words(b))
+    }
+
+    #[inline]
+    pub fn square(&self, a: T) -> T {
+        self.mul(a, a)
+    }
+
+    /// Raises `base` to `exp` with a sliding window, in variable time.
+    pub fn pow(&self, base: T, exp: T) -> T {
+        pow::sliding_window(T::ONE, base, exp, |a, b| self.mul(a, b))
+    }
+
+    /// Raises `base` to `exp` with a Montgomery ladder, in constant time.
+    pub fn pow_ct(&self, base: T, exp: T) -> T {
+        pow::ladder(T::ONE, base, exp, |a, b| self.mul(a, b))
+    }
+}
+
+/// Rows `2^(k + 32 j) mod modulus` covering every digit of a `bits` wide
+/// value, or `None` if the modulus is too large or a row is not sparse.
+const fn table(words: &[u64], top: usize, k: u32, bits: u32) -> Option<[Row; MAX_ROWS]> {
+    if top > TABLE_WORDS || (bits - k).div_ceil(32) as usize > MAX_ROWS {
+        return None;
+    }
+
+    let mut modulus = [0u64; TABLE_WORDS];
+    let mut i = 0;
+    while i < top {
+        modulus[i] = words[i];
+        i += 1;
+    }
+
+    // 2^k mod modulus = c = -modulus mod 2^k
+    let mut row = [0u64; TABLE_WORDS];
+    let mut i = 0;
+    while i < k {
+        row[i as usize / 64] |= c_bit(words, k, i) << (i % 64);
+        i += 1;
+    }
+
+    let mut rows = [([(false, 0); MAX_TERMS], 0); MAX_ROWS];
+    let mut j = 0;
+
+    while j < (bits - k).div_ceil(32) as usize {
+        rows[j] = match naf(&row, k) {
+            Some(terms) => terms,
+            None => return None,
+        };
+
+        let mut i = 0;
+        while i < 32 {
+            row = field::double_mod(row, &modulus);
+            i += 1;
+        }
+
+        j += 1;
+    }
+
+    Some(rows)
+}
+
+/// Non-adjacent form of a `bits` wide value, scanning its bits with a carry.
+const fn naf(words: &[u64; TABLE_WORDS], bits: u32) -> Option<Row> {
+    let mut terms = [(false, 0); MAX_TERMS];
+    let mut len = 0;
+    let mut carry = 0;
+    let mut i = 0;
+
+    while i <= bits {
+        let digit = bit(words, i) + carry;
+
+        if digit == 1 {
+            if len == MAX_TERMS {
+                return None;
+            }
+
+            let negative = bit(words, i + 1) == 1;
+            terms[len] = (negative, i);
+            len += 1;
+            carry = negative as u64;
+        } else {
+            carry = digit >> 1;
+        }
+
+        i += 1;
+    }
+
+    Some((terms, len))
+}
+
+const fn bit(words: &[u64], idx: u32) -> u64 {
+    if (idx as usize / 64) < words.len() {
+        words[idx as usize / 64] >> (idx % 64) & 1
+    } else {
+        0
+    }
+}
+
+const fn min(a: u32, b: u32) -> u32 {
+    if a < b {
+        a
+    } else {
+        b
+    }
+}
+
+const fn max(a: u32, b: u32) -> u32 {
+    if a > b {
+        a
+    } else {
+        b
+    }
+}
+
+/// The 32 bits of `words` starting at bit `pos`, which must be in range.
+fn read_u32(words: &[u64], pos: u32) -> u64 {
+    let (idx, shift) = (pos as usize / 64, pos % 64);
+    let mut ret = words[idx] >> shift;
+
+    if shift > 32 && idx + 1 < words.len() {
+        ret |= words[idx + 1] << (64 - shift);
+    }
+
+    ret & 0xffff_ffff
+}
+
+/// Bit `idx` of `c = 2^k - modulus`, the low `k` bits of `-modulus`.
+const fn c_bit(modulus: &[u64], k: u32, idx: u32) -> u64 {
+    if idx >= k {
+        return 0;
+    }
+
+    let bit = modulus[idx as usize / 64] >> (idx % 64) & 1;
+    let mut below = idx as usize / 64;
+    let mut lower_set = modulus[below] & ((1 << (idx % 64)) - 1) != 0;
+
+    while !lower_set && below > 0 {
+        below -= 1;
+        lower_set = modulus[below] != 0;
+    }
+
+    // two's complement negation keeps bits up to the lowest set one and flips the rest
+    if lower_set {
+        bit ^ 1
+    } else {
+        bit
+    }
+}
+
+/// `x = (x mod 2^k) + (x >> k) * c` in a single pass. Every step only reads
+/// words at or above the one it writes, so this works in place.
+fn fold_word(x: &mut [u64], k: u32, c: u64) {
+    let (word_shift, bit_shift) = ((k / 64) as usize, k % 64);
+    let mut carry = 0u128;
+
+    for i in 0..x.len() {
+        let low = match i.cmp(&word_shift) {
+            Ordering::Less => x[i],
+            Ordering::Equal => x[i] & ((1 << bit_shift) - 1),
+            Ordering::Greater => 0,
+        };
+
+        let j = i + word_shift;
+        let mut high = x.get(j).map_or(0, |word| word >> bit_shift);
+        if bit_shift != 0 {
+            high |= x.get(j + 1).map_or(0, |word| word << (64 - bit_shift));
+        }
+
+        let t = low as u128 + high as u128 * c as u128 + carry;
+        x[i] = t as u64;
+        carry = t >> 64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::limbs::xorshift;
+    use crate::{u24, u256, u512, Solinas};
+
+    #[test]
+    fn special_forms() {
+        let p25519 = (u256::ONE << 255) - u256::from(19);
+        let k256 = u256::MAX - (u256::ONE << 32) - u256::from(976);
+        let p256 = u256::MAX - (u256::ONE << 224) + (u256::ONE << 192) + (u256::ONE << 96);
+        let a = u256::MAX - u256::from(12345);
+        let b = u256::from(u128::MAX) << 99;
+
+        for p in [p25519, k256, p256] {
+            let ctx = Solinas::new(p).unwrap();
+
+            assert_eq!(ctx.mul(a, b), a.mul_mod(b, p));
+            assert_eq!(ctx.square(u256::MAX), u256::MAX.mul_mod(u256::MAX, p));
+            assert_eq!(ctx.mul(p - u256::ONE, p - u256::ONE), u256::ONE);
+            assert_eq!(ctx.mul(p, a), u256::ZERO);
+            assert_eq!(ctx.reduce(u256::MAX, u256::ZERO), u256::MAX % p);
+
+            let (low, high) = a.widening_mul(b);
+            assert_eq!(ctx.reduce(low, high), a.mul_mod(b, p));
+            assert_eq!(ctx.pow(a, p - u256::ONE), u256::ONE);
+            assert_eq!(ctx.pow_ct(a, p - u256::from(2)), a.inv_mod(p).unwrap());
+        }
+
+        // xorshift operands against the generic reduction
+        let mut rng = xorshift(0x2545_f491_4f6c_dd1d);
+        let mut next = || u256::from_le_bytes([0u8; 32].map(|_| rng() as u8));
+
+        for p in [p25519, k256, p256] {
+            let ctx = Solinas::new(p).unwrap();
+
+            for _ in 0..200 {
+                let (a, b) = (next(), next());
+                assert_eq!(ctx.mul(a, b), a.mul_mod(b, p));
+            }
+        }
+
+        // P-384 only fits u512, which folds twenty digits above 2^384
+        let p384 = u512::from_str_radix(
+            "fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffe\
+             ffffffff0000000000000000ffffffff",
+            16,
+        )
+        .unwrap();
+        let ctx = Solinas::new(p384).unwrap();
+
+        for _ in 0..200 {
+            let a = u512::from_le_bytes([0u8; 64].map(|_| rng() as u8));
+            let b = u512::from_le_bytes([0u8; 64].map(|_| rng() as u8));
+            assert_eq!(ctx.mul(a, b), a.mul_mod(b, p384));
+        }
+        assert_eq!(ctx.square(u512::MAX), u512::MAX.mul_mod(u512::MAX, p384));
+        assert_eq!(
+            ctx.pow_ct(u512::from(3), p384 - u512::from(2)),
+            u512::from(3).inv_mod(p384).unwrap()
+        );
+
+        let ctx = Solinas::new(u24::from((1 << 23) - 15)).unwrap();
+        assert_eq!(
+            ctx.mul(u24::MAX, u24::MAX),
+            u24::MAX.mul_mod(u24::MAX, u24::from((1 << 23) - 15))
+        );
+
+        // c is neither word sized nor sparse, or too close to 2^k
+        assert!(Solinas::new((u256::ONE << 255) - ((u256::MAX / u256::from(3)) >> 120)).is_none());
+        assert!(Solinas::new(u256::MAX / u256::from(3)).is_none());
+        assert!(Solinas::new((u256::ONE << 255) + u256::ONE).is_none());
+    }
+}
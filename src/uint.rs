@@ -0,0 +1,65 @@
+//! Generic access to the `bituint` generated types.
+
+use std::fmt;
+
+/// Implemented by every `#[bituint]` type, exposing its little-endian `u64`
+/// word form to generic code such as [`Montgomery`](crate::Montgomery).
+pub trait BitUint: Copy + Eq + Ord + Default + fmt::Debug {
+    /// Size of the type in bits.
+    const BITS: u32;
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// `[u64; N]` with `N = BITS.div_ceil(64)`.
+    type Words: Copy + AsRef<[u64]> + AsMut<[u64]>;
+    /// `[u64; 2 * N]`, holding a full product.
+    type WideWords: Copy + AsRef<[u64]> + AsMut<[u64]>;
+
+    /// Splits the value into little-endian `u64` words.
+    fn to_words(self) -> Self::Words;
+
+    /// Packs little-endian `u64` words, ignoring bits past `BITS`.
+    fn from_words(words: Self::Words) -> Self;
+
+    /// Full product `self * rhs` as little-endian `u64` words.
+    ///
+    /// Always a schoolbook product, so it takes the same time for every value at
+    /// any width; the reductions rely on it for their constant time methods.
+    fn widening_mul_words(self, rhs: Self) -> Self::WideWords;
+}
+
+/// Primitive integers accepted as the amount of a `<<` or `>>` on the generated
+/// types, as the primitive types accept any of them.
+///
+/// Sealed: the generated types convert to `u32` by truncation, so they must not
+/// pass as amounts themselves.
+pub trait ShiftAmount: Copy + sealed::Sealed {
+    /// `self` reduced modulo `bits`, never negative, and whether it was outside
+    /// `0..bits`.
+    fn reduce(self, bits: u32) -> (u32, bool);
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+macro_rules! impl_shift_amount {
+    ($wide:ty => $($t:ty),*) => {
+        $(
+            impl sealed::Sealed for $t {}
+
+            impl ShiftAmount for $t {
+                #[inline]
+                fn reduce(self, bits: u32) -> (u32, bool) {
+                    let value = self as $wide;
+                    let bits = <$wide>::from(bits);
+
+                    (value.rem_euclid(bits) as u32, !(0..bits).contains(&value))
+                }
+            }
+        )*
+    };
+}
+
+impl_shift_amount!(u128 => u8, u16, u32, u64, u128, usize);
+impl_shift_amount!(i128 => i8, i16, i32, i64, i128, isize);